@@ -0,0 +1,8 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+// Feeds arbitrary text into the heuristic extractor
+fuzz_target!(|data: &str| {
+    data_collection::recipes::fuzzing::heuristic(data);
+});
@@ -0,0 +1,9 @@
+#![no_main]
+
+use data_collection::utils::U32Ext;
+use libfuzzer_sys::fuzz_target;
+
+// Feeds arbitrary text into the "1 hour 30 minutes" duration parser
+fuzz_target!(|data: &str| {
+    let _ = u32::from_time_str(data);
+});
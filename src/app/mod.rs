@@ -0,0 +1,455 @@
+use regex::Regex;
+use reqwest::Url;
+use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::recipes::merge::ConflictPolicy;
+use crate::recipes::retry::RetryPolicy;
+use crate::recipes::throttle::{RateLimit, MAX_WAIT};
+
+#[cfg(feature = "db")]
+use crate::db;
+#[cfg(feature = "db")]
+use crate::discovery::{self, SitemapOptions};
+#[cfg(feature = "db")]
+use crate::error::{self, ErrorCode, ScrapeError};
+#[cfg(feature = "db")]
+use crate::hooks::Hooks;
+#[cfg(feature = "db")]
+use crate::recipes::fetch::Fetcher;
+#[cfg(feature = "db")]
+use crate::recipes::{get_archive_recipes, get_listing_recipes, get_recipes_from_urls, Recipe};
+#[cfg(feature = "db")]
+use crate::summary::{exit, Failure, RunSummary};
+#[cfg(feature = "db")]
+use std::path::Path;
+#[cfg(feature = "db")]
+use surrealdb::{engine::remote::ws::Client, Surreal};
+
+/// Settings for a run, read from the command line.
+///
+/// # Fields
+///
+/// - `trace_parse`: `--trace-parse`, write how each field was parsed to `./trace`.
+/// - `unpublish_galleries`: `--unpublish-galleries`, remove stored recipes whose pages became galleries.
+/// - `summary_out`: `--summary-out <path>`, write a JSON run summary for wrapper scripts.
+/// - `policy`: `--on-conflict <prefer-newest|prefer-existing|merge>`, how re-scrapes are reconciled.
+/// - `sitemap`: `--sitemap <url>`, scrape every recipe in the site's sitemap instead of a listing page.
+/// - `recipe_pattern`: `--recipe-pattern <regex>`, which sitemap URLs are recipes (see `discovery::DEFAULT_RECIPE_PATTERN`).
+/// - `archive`: `--archive <url>`, scrape every post in a category or tag archive instead of a listing page.
+/// - `rate_limit`: `--rps <n>` and `--jitter-ms <n>`, how fast each site is fetched (`--rps 0` disables the limit, and neither may wait longer than `throttle::MAX_WAIT`).
+/// - `retry`: `--max-attempts <n>` and `--backoff-ms <n>`, how often and how patiently failed fetches are retried.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Options {
+    pub trace_parse: bool,
+    pub unpublish_galleries: bool,
+    pub summary_out: Option<PathBuf>,
+    pub policy: ConflictPolicy,
+    pub sitemap: Option<String>,
+    pub recipe_pattern: Option<String>,
+    pub archive: Option<String>,
+    pub rate_limit: RateLimit,
+    pub retry: RetryPolicy,
+}
+
+impl Options {
+    /// Parses the options from the process arguments (the first is the program name).
+    ///
+    /// # Errors
+    ///
+    /// Returns a message if a flag is missing its value or the value is invalid.
+    pub fn from_args(args: &[String]) -> Result<Self, String> {
+        let value = |flag: &str| -> Result<Option<&String>, String> {
+            match args.iter().position(|arg| arg == flag) {
+                Some(i) => args
+                    .get(i + 1)
+                    .map(Some)
+                    .ok_or(format!("{} requires a value", flag)),
+                None => Ok(None),
+            }
+        };
+
+        Ok(Options {
+            trace_parse: args.iter().any(|arg| arg == "--trace-parse"),
+            unpublish_galleries: args.iter().any(|arg| arg == "--unpublish-galleries"),
+            summary_out: value("--summary-out")?.map(PathBuf::from),
+            policy: match value("--on-conflict")? {
+                Some(p) => ConflictPolicy::from(p).map_err(|e| e.to_string())?,
+                None => ConflictPolicy::default(),
+            },
+            sitemap: value("--sitemap")?.cloned(),
+            recipe_pattern: match value("--recipe-pattern")? {
+                Some(p) => Some(
+                    Regex::new(p)
+                        .map(|_| p.clone())
+                        .map_err(|e| format!("Invalid --recipe-pattern: {}", e))?,
+                ),
+                None => None,
+            },
+            archive: value("--archive")?.cloned(),
+            rate_limit: RateLimit {
+                rps: match value("--rps")? {
+                    Some(rps) => rps
+                        .parse::<f64>()
+                        .ok()
+                        .filter(|rps| rps.is_finite() && *rps >= 0.0)
+                        .filter(|rps| *rps == 0.0 || 1.0 / rps <= MAX_WAIT.as_secs_f64())
+                        .ok_or(format!("Invalid --rps: {}", rps))?,
+                    None => RateLimit::default().rps,
+                },
+                jitter: match value("--jitter-ms")? {
+                    Some(ms) => ms
+                        .parse()
+                        .ok()
+                        .map(Duration::from_millis)
+                        .filter(|jitter| *jitter <= MAX_WAIT)
+                        .ok_or(format!("Invalid --jitter-ms: {}", ms))?,
+                    None => RateLimit::default().jitter,
+                },
+            },
+            retry: RetryPolicy {
+                max_attempts: match value("--max-attempts")? {
+                    Some(n) => n
+                        .parse::<u32>()
+                        .ok()
+                        .filter(|n| *n >= 1)
+                        .ok_or(format!("Invalid --max-attempts: {}", n))?,
+                    None => RetryPolicy::default().max_attempts,
+                },
+                base_delay: match value("--backoff-ms")? {
+                    Some(ms) => Duration::from_millis(
+                        ms.parse()
+                            .map_err(|_| format!("Invalid --backoff-ms: {}", ms))?,
+                    ),
+                    None => RetryPolicy::default().base_delay,
+                },
+                ..RetryPolicy::default()
+            },
+        })
+    }
+}
+
+/// The record ID for a recipe, built from its URL's host and path so recipes from
+/// different sites don't collide. Only letters, digits, `-`, `_` and `.` are kept, so
+/// the ID is also a safe file name for parse traces.
+///
+/// Recipes from aheadofthyme.com keep the bare slug they have always been stored under
+/// (`easy-meat-lasagna`); other sites get their host in front
+/// (`example.com_recipes-minestrone-soup`).
+pub fn record_id(url: &str) -> String {
+    let (host, path) = match Url::parse(url) {
+        Ok(url) => (
+            url.host_str()
+                .unwrap_or_default()
+                .trim_start_matches("www.")
+                .to_string(),
+            url.path().to_string(),
+        ),
+        Err(_) => (String::new(), url.to_string()),
+    };
+
+    let slug = path
+        .split(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+
+    if host.is_empty() || host == "aheadofthyme.com" {
+        slug
+    } else if slug.is_empty() {
+        host
+    } else {
+        format!("{}_{}", host, slug)
+    }
+}
+
+/// Everything a run needs, built once in `main` and passed to the pipeline instead of
+/// each step constructing its own.
+#[cfg(feature = "db")]
+pub struct App {
+    pub db: Surreal<Client>,
+    pub hooks: Hooks,
+    pub options: Options,
+    pub fetcher: Box<dyn Fetcher>,
+}
+
+#[cfg(feature = "db")]
+impl App {
+    pub fn new(
+        db: Surreal<Client>,
+        hooks: Hooks,
+        options: Options,
+        fetcher: Box<dyn Fetcher>,
+    ) -> Self {
+        App {
+            db,
+            hooks,
+            options,
+            fetcher,
+        }
+    }
+
+    /// Scrapes every recipe on a listing page and the pages it paginates to, runs the
+    /// hooks and stores the results.
+    ///
+    /// # Returns
+    ///
+    /// - A `RunSummary` with its exit code set.
+    pub async fn crawl(&self, listing: &str) -> RunSummary {
+        let mut summary = RunSummary::default();
+        let mut page_failures = Vec::new();
+
+        let recipes = match get_listing_recipes(
+            self.fetcher.as_ref(),
+            listing,
+            &mut page_failures,
+            &mut summary.failures,
+        )
+        .await
+        {
+            Ok(recipes) => recipes,
+            Err(e) => {
+                println!("Cannot get doc! {}", error::describe(&*e));
+                summary.fail(listing, &*e);
+                summary.exit_code = exit::TOTAL_FAILURE;
+                return summary;
+            }
+        };
+
+        self.store_all(recipes, page_failures, summary).await
+    }
+
+    /// Scrapes every recipe URL in a sitemap (following sitemap indexes), runs the
+    /// hooks and stores the results. URLs are filtered by `options.recipe_pattern`.
+    ///
+    /// # Returns
+    ///
+    /// - A `RunSummary` with its exit code set.
+    pub async fn crawl_sitemap(&self, sitemap: &str) -> RunSummary {
+        let mut summary = RunSummary::default();
+
+        let mut sitemap_options = SitemapOptions::default();
+        if let Some(pattern) = &self.options.recipe_pattern {
+            // Validated by `Options::from_args`
+            sitemap_options.pattern = Regex::new(pattern).unwrap();
+        }
+
+        let urls =
+            match discovery::sitemap_urls(self.fetcher.as_ref(), sitemap, &sitemap_options).await {
+                Ok(urls) => urls,
+                Err(e) => {
+                    println!("Cannot get sitemap! {}", error::describe(&*e));
+                    summary.fail(sitemap, &*e);
+                    summary.exit_code = exit::TOTAL_FAILURE;
+                    return summary;
+                }
+            };
+        println!("Found {} recipe URLs in {}", urls.len(), sitemap);
+
+        let recipes =
+            get_recipes_from_urls(self.fetcher.as_ref(), &urls, &mut summary.failures).await;
+        self.store_all(recipes, Vec::new(), summary).await
+    }
+
+    /// Scrapes every post in a category or tag archive, across all of its pages, runs
+    /// the hooks and stores the results with the archive recorded in their
+    /// `discovered_under`.
+    ///
+    /// # Returns
+    ///
+    /// - A `RunSummary` with its exit code set.
+    pub async fn crawl_archive(&self, archive: &str) -> RunSummary {
+        let mut summary = RunSummary::default();
+        let mut page_failures = Vec::new();
+
+        let recipes = match get_archive_recipes(
+            self.fetcher.as_ref(),
+            archive,
+            &mut page_failures,
+            &mut summary.failures,
+        )
+        .await
+        {
+            Ok(recipes) => recipes,
+            Err(e) => {
+                println!("Cannot get archive! {}", error::describe(&*e));
+                summary.fail(archive, &*e);
+                summary.exit_code = exit::TOTAL_FAILURE;
+                return summary;
+            }
+        };
+
+        self.store_all(recipes, page_failures, summary).await
+    }
+
+    /// Runs the hooks on scraped recipes and stores them, recording the outcome in
+    /// `summary` alongside the scrape failures already in it.
+    ///
+    /// Listing pages that failed to load (`page_failures`) fail the run, but are not
+    /// counted in `found`, which counts only recipe URLs. With `--unpublish-galleries`,
+    /// galleries are moved from `failures` to `unpublished` once handled.
+    async fn store_all(
+        &self,
+        recipes: Vec<Recipe>,
+        page_failures: Vec<Failure>,
+        mut summary: RunSummary,
+    ) -> RunSummary {
+        summary.found = recipes.len() + summary.failures.len();
+        summary.failures.extend(page_failures);
+
+        // Galleries that were unpublished are handled, so they no longer fail the run
+        if self.options.unpublish_galleries {
+            let mut failures = Vec::new();
+            for failure in std::mem::take(&mut summary.failures) {
+                if failure.code != ErrorCode::NotARecipe {
+                    failures.push(failure);
+                    continue;
+                }
+
+                let id = record_id(&failure.url);
+                match db::unpublish_recipe(&self.db, &id).await {
+                    Ok(removed) => {
+                        if removed.is_some() {
+                            println!("UNPUBLISHED: {}", id);
+                        }
+                        summary.unpublished.push(failure.url);
+                    }
+                    Err(e) => {
+                        println!("Failed to unpublish {}: {}", id, e);
+                        failures.push(failure);
+                    }
+                }
+            }
+            summary.failures = failures;
+        }
+
+        for mut rec in recipes {
+            if let Err(e) = self.hooks.run(&mut rec).await {
+                println!("Skipping {}: {}", rec.url, error::describe(&*e));
+                summary.fail(&rec.url, &*e);
+                continue;
+            }
+
+            let id = record_id(&rec.url);
+
+            if self.options.trace_parse {
+                if let Err(e) = rec.trace.write_to(Path::new("trace"), &id) {
+                    println!("Failed to write parse trace for {}: {}", id, e);
+                }
+            }
+
+            println!("WROTE: {}", id);
+
+            let url = rec.url.clone();
+            let _: Option<Recipe> =
+                match db::store_recipe(&self.db, &id, rec, self.options.policy).await {
+                    Ok(res) => {
+                        println!("Sucess");
+                        summary.stored += 1;
+                        res
+                    }
+                    Err(e) => {
+                        println!("Failure: [{}] {}", ErrorCode::DbWrite, e);
+                        summary.fail(&url, &ScrapeError::new(ErrorCode::DbWrite, e.to_string()));
+                        None
+                    }
+                };
+        }
+
+        summary.finish();
+        summary
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_record_id() {
+        assert_eq!(
+            record_id("https://www.aheadofthyme.com/easy-meat-lasagna/"),
+            "easy-meat-lasagna"
+        );
+        assert_eq!(
+            record_id("https://example.com/recipes/minestrone-soup/?utm_source=feed#comments"),
+            "example.com_recipes-minestrone-soup"
+        );
+        assert_eq!(
+            record_id("http://www.example.org/2024/05/chili"),
+            "example.org_2024-05-chili"
+        );
+        assert_eq!(record_id("https://example.com/"), "example.com");
+
+        let id = record_id("https://cooking.example.net/dal/");
+        assert_eq!(id, "cooking.example.net_dal");
+        assert!(!id.contains(':') && !id.contains('/'));
+    }
+
+    fn args(line: &str) -> Vec<String> {
+        line.split_whitespace().map(String::from).collect()
+    }
+
+    #[test]
+    fn test_options_from_args() {
+        let options = Options::from_args(&args(
+            "data_collection --trace-parse --summary-out run.json --on-conflict prefer-newest",
+        ))
+        .expect("Failed to parse options");
+
+        assert!(options.trace_parse);
+        assert!(!options.unpublish_galleries);
+        assert_eq!(options.summary_out, Some(PathBuf::from("run.json")));
+        assert_eq!(options.policy, ConflictPolicy::PreferNewest);
+
+        assert_eq!(
+            Options::from_args(&args("data_collection")).unwrap(),
+            Options::default()
+        );
+        assert!(Options::from_args(&args("data_collection --summary-out")).is_err());
+        assert!(Options::from_args(&args("data_collection --on-conflict bogus")).is_err());
+
+        let options = Options::from_args(&args(
+            "data_collection --sitemap https://www.aheadofthyme.com/sitemap_index.xml --recipe-pattern /recipes/",
+        ))
+        .expect("Failed to parse options");
+        assert_eq!(
+            options.sitemap.as_deref(),
+            Some("https://www.aheadofthyme.com/sitemap_index.xml")
+        );
+        assert_eq!(options.recipe_pattern.as_deref(), Some("/recipes/"));
+        assert!(Options::from_args(&args("data_collection --recipe-pattern (")).is_err());
+
+        let options = Options::from_args(&args(
+            "data_collection --archive https://www.aheadofthyme.com/category/dinner/",
+        ))
+        .expect("Failed to parse options");
+        assert_eq!(
+            options.archive.as_deref(),
+            Some("https://www.aheadofthyme.com/category/dinner/")
+        );
+
+        let options = Options::from_args(&args("data_collection --rps 0.5 --jitter-ms 0"))
+            .expect("Failed to parse options");
+        assert_eq!(
+            options.rate_limit,
+            RateLimit {
+                rps: 0.5,
+                jitter: Duration::ZERO
+            }
+        );
+        assert!(Options::from_args(&args("data_collection --rps -1")).is_err());
+        assert!(Options::from_args(&args("data_collection --jitter-ms soon")).is_err());
+        assert!(Options::from_args(&args("data_collection --rps 1e-300")).is_err());
+        assert!(
+            Options::from_args(&args("data_collection --jitter-ms 18446744073709551615")).is_err()
+        );
+
+        let options =
+            Options::from_args(&args("data_collection --max-attempts 1 --backoff-ms 250"))
+                .expect("Failed to parse options");
+        assert_eq!(options.retry.max_attempts, 1);
+        assert_eq!(options.retry.base_delay, Duration::from_millis(250));
+        assert!(Options::from_args(&args("data_collection --max-attempts 0")).is_err());
+    }
+}
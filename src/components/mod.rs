@@ -0,0 +1,191 @@
+use std::future::Future;
+use surrealdb::{engine::remote::ws::Client, Surreal};
+
+use crate::db::RECIPES;
+use crate::recipes::Recipe;
+
+/// How many levels of sub-recipes are expanded (a lasagna using a sauce using a stock).
+const MAX_DEPTH: usize = 3;
+
+/// Gets the stored recipe scraped from `url`.
+pub async fn by_url(db: &Surreal<Client>, url: &str) -> Result<Option<Recipe>, surrealdb::Error> {
+    let recipes: Vec<Recipe> = db
+        .query("SELECT * FROM type::table($table) WHERE url = $url LIMIT 1")
+        .bind(("table", RECIPES))
+        .bind(("url", url.to_string()))
+        .await?
+        .take(0)?;
+
+    Ok(recipes.into_iter().next())
+}
+
+/// Lists every stored recipe that uses the recipe at `url` as an ingredient
+/// (all recipes using this sauce).
+pub async fn used_by(db: &Surreal<Client>, url: &str) -> Result<Vec<Recipe>, surrealdb::Error> {
+    db.query("SELECT * FROM type::table($table) WHERE $url IN ingredients.sub_recipe")
+        .bind(("table", RECIPES))
+        .bind(("url", url.to_string()))
+        .await?
+        .take(0)
+}
+
+/// One line of a recipe's expanded ingredient list.
+///
+/// # Fields
+///
+/// - `name`: The ingredient name.
+/// - `quantity`: The quantity as written in the recipe it comes from (zero when none is
+///   given), not scaled to how much of a sub-recipe is used.
+/// - `unit`: The name of its unit, if it has one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExpandedIngredient {
+    pub name: String,
+    pub quantity: f32,
+    pub unit: Option<&'static str>,
+}
+
+/// The recipe's full ingredient list, with every linked sub-recipe that has been
+/// scraped replaced by its own ingredients, up to `MAX_DEPTH` levels deep.
+///
+/// A sub-recipe is expanded every time it is used, however it is reached. Sub-recipes
+/// that are not stored, or that link back to a recipe on the path that led to them, are
+/// kept as a single ingredient.
+pub async fn expanded_ingredients(
+    db: &Surreal<Client>,
+    recipe: &Recipe,
+) -> Result<Vec<ExpandedIngredient>, surrealdb::Error> {
+    expand(recipe, |url| async move { by_url(db, &url).await }).await
+}
+
+/// A pending ingredient of the walk in `expand`: its line, the sub-recipe it links to,
+/// and the URLs of the recipes that led to it.
+type Pending = (ExpandedIngredient, Option<String>, Vec<String>);
+
+/// The ingredient lines of `recipe`, in order, each reached through `path`.
+fn pending(recipe: &Recipe, path: &[String]) -> Vec<Pending> {
+    recipe
+        .components()
+        .zip(recipe.ingredient_amounts())
+        .map(|((name, link), (_, quantity, unit))| {
+            let ingredient = ExpandedIngredient {
+                name: name.to_string(),
+                quantity,
+                unit,
+            };
+            (ingredient, link.map(str::to_string), path.to_vec())
+        })
+        .collect()
+}
+
+/// Walks `recipe`'s ingredients depth-first, looking up linked sub-recipes with `lookup`.
+async fn expand<F, Fut, E>(recipe: &Recipe, lookup: F) -> Result<Vec<ExpandedIngredient>, E>
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = Result<Option<Recipe>, E>>,
+{
+    let mut out = Vec::new();
+
+    // Reversed so popping walks the list in order
+    let mut stack = pending(recipe, std::slice::from_ref(&recipe.url));
+    stack.reverse();
+
+    while let Some((ingredient, link, path)) = stack.pop() {
+        let sub = match link {
+            Some(url) if path.len() <= MAX_DEPTH && !path.contains(&url) => {
+                lookup(url.clone()).await?.map(|sub| (sub, url))
+            }
+            _ => None,
+        };
+
+        match sub {
+            Some((sub, url)) => {
+                let mut path = path;
+                path.push(url);
+                stack.extend(pending(&sub, &path).into_iter().rev());
+            }
+            None => out.push(ingredient),
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    /// A recipe at `url` whose ingredients are `(name, sub-recipe URL)` pairs.
+    fn recipe(url: &str, ingredients: &[(&str, Option<&str>)]) -> Recipe {
+        let mut json = serde_json::to_value(Recipe::default()).unwrap();
+        json["url"] = url.into();
+        json["ingredients"] = ingredients
+            .iter()
+            .map(|(name, link)| serde_json::json!({ "name": name, "quantity": 1.0, "sub_recipe": link }))
+            .collect();
+        serde_json::from_value(json).expect("Failed to build recipe")
+    }
+
+    async fn expand_names(recipe: &Recipe, stored: &[Recipe]) -> Vec<String> {
+        let stored: HashMap<&str, &Recipe> = stored.iter().map(|r| (r.url.as_str(), r)).collect();
+        let lookup = |url: String| {
+            let found = stored.get(url.as_str()).map(|r| (*r).clone());
+            async move { Ok::<_, ()>(found) }
+        };
+
+        expand(recipe, lookup)
+            .await
+            .unwrap()
+            .into_iter()
+            .map(|i| i.name)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_expand() {
+        let stock = recipe("/stock/", &[("bones", None), ("water", None)]);
+        let sauce = recipe("/sauce/", &[("tomatoes", None), ("stock", Some("/stock/"))]);
+        // Links back to the lasagna, which is on its own path
+        let bechamel = recipe(
+            "/bechamel/",
+            &[("milk", None), ("lasagna", Some("/lasagna/"))],
+        );
+        let lasagna = recipe(
+            "/lasagna/",
+            &[
+                ("sauce", Some("/sauce/")),
+                ("stock", Some("/stock/")),
+                ("bechamel", Some("/bechamel/")),
+                ("pesto", Some("/pesto/")),
+            ],
+        );
+
+        let names = expand_names(&lasagna, &[stock, sauce, bechamel, lasagna.clone()]).await;
+
+        // The stock is expanded both inside the sauce and on its own
+        assert_eq!(
+            names,
+            vec!["tomatoes", "bones", "water", "bones", "water", "milk", "lasagna", "pesto"]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_expand_keeps_quantities() {
+        let mut json = serde_json::to_value(Recipe::default()).unwrap();
+        json["ingredients"] =
+            serde_json::json!([{ "name": "flour", "quantity": 2.5, "units": "CUP" }]);
+        let bread: Recipe = serde_json::from_value(json).unwrap();
+
+        let expanded = expand(&bread, |_| async { Ok::<_, ()>(None) })
+            .await
+            .unwrap();
+        assert_eq!(
+            expanded,
+            vec![ExpandedIngredient {
+                name: "flour".into(),
+                quantity: 2.5,
+                unit: Some("cup"),
+            }]
+        );
+    }
+}
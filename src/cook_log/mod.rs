@@ -0,0 +1,175 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::error::Error;
+use surrealdb::{engine::remote::ws::Client, Surreal};
+
+use crate::overlay;
+use crate::utils::parse_iso_date;
+
+const TABLE: &str = "cook_log";
+
+/// A single time a recipe was cooked.
+///
+/// # Fields
+///
+/// - `recipe_id`: The ID of the recipe record that was cooked.
+/// - `cooked_on`: The date it was cooked, as `YYYY-MM-DD`.
+/// - `servings`: How many people it was cooked for.
+/// - `outcome`: Free-form notes on how it turned out.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct CookLogEntry {
+    pub recipe_id: String,
+    pub cooked_on: String,
+    pub servings: Option<u32>,
+    pub outcome: Option<String>,
+}
+
+/// Records a cooking and bumps the recipe overlay's `cooked_count` and `last_cooked`.
+///
+/// The entry is written first, so a failed write never leaves the overlay counting a
+/// cooking that is not in the log.
+///
+/// # Errors
+///
+/// Returns an error if `cooked_on` is not a valid `YYYY-MM-DD` date, or a write fails.
+pub async fn log(
+    db: &Surreal<Client>,
+    mut entry: CookLogEntry,
+) -> Result<Option<CookLogEntry>, Box<dyn Error>> {
+    entry.cooked_on = parse_iso_date(&entry.cooked_on)?;
+    let created: Option<CookLogEntry> = db.create(TABLE).content(entry).await?;
+    if let Some(created) = &created {
+        overlay::mark_cooked(db, &created.recipe_id, &created.cooked_on).await?;
+    }
+
+    Ok(created)
+}
+
+/// Writes an entry under its exported ID without touching the recipe overlay, for
+/// restoring backups. Restoring the same entry twice leaves a single entry.
+pub async fn restore(
+    db: &Surreal<Client>,
+    id: &str,
+    entry: CookLogEntry,
+) -> Result<Option<CookLogEntry>, surrealdb::Error> {
+    db.upsert((TABLE, id)).content(entry).await
+}
+
+/// Lists every cook log entry.
+pub async fn entries(db: &Surreal<Client>) -> Result<Vec<CookLogEntry>, surrealdb::Error> {
+    db.select(TABLE).await
+}
+
+/// Lists every cook log entry with its record ID, for backups.
+pub async fn list(db: &Surreal<Client>) -> Result<Vec<(String, CookLogEntry)>, surrealdb::Error> {
+    #[derive(Deserialize)]
+    struct Row {
+        entry_id: String,
+        #[serde(flatten)]
+        entry: CookLogEntry,
+    }
+
+    let rows: Vec<Row> = db
+        .query("SELECT *, record::id(id) AS entry_id FROM type::table($table)")
+        .bind(("table", TABLE))
+        .await?
+        .take(0)?;
+
+    Ok(rows.into_iter().map(|r| (r.entry_id, r.entry)).collect())
+}
+
+/// The `n` most cooked recipes with their counts, most cooked first.
+pub fn most_cooked(entries: &[CookLogEntry], n: usize) -> Vec<(String, usize)> {
+    let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+    for entry in entries {
+        *counts.entry(&entry.recipe_id).or_default() += 1;
+    }
+
+    let mut counts: Vec<(String, usize)> = counts
+        .into_iter()
+        .map(|(id, count)| (id.to_string(), count))
+        .collect();
+    counts.sort_by_key(|c| std::cmp::Reverse(c.1));
+    counts.truncate(n);
+    counts
+}
+
+/// Recipes in the log that have not been cooked within `days` days of `today`
+/// (`YYYY-MM-DD`), with the date they were last cooked, oldest first.
+pub fn not_cooked_since(entries: &[CookLogEntry], today: &str, days: i64) -> Vec<(String, String)> {
+    let Some(today) = day_number(today) else {
+        return Vec::new();
+    };
+
+    let mut last: BTreeMap<&str, &str> = BTreeMap::new();
+    for entry in entries {
+        let date = last.entry(&entry.recipe_id).or_insert(&entry.cooked_on);
+        if entry.cooked_on.as_str() > *date {
+            *date = &entry.cooked_on;
+        }
+    }
+
+    let mut stale: Vec<(String, String)> = last
+        .into_iter()
+        .filter(|(_, date)| day_number(date).is_some_and(|d| today - d > days))
+        .map(|(id, date)| (id.to_string(), date.to_string()))
+        .collect();
+    stale.sort_by(|a, b| a.1.cmp(&b.1));
+    stale
+}
+
+/// Converts a `YYYY-MM-DD` date into a count of days since 1970-01-01, or `None` if it
+/// isn't a real date.
+fn day_number(date: &str) -> Option<i64> {
+    let date = parse_iso_date(date).ok()?;
+    let mut parts = date.splitn(3, '-').map(|p| p.parse::<i64>().ok());
+    let (y, m, d) = (parts.next()??, parts.next()??, parts.next()??);
+
+    // Days from civil, see http://howardhinnant.github.io/date_algorithms.html
+    let y = if m <= 2 { y - 1 } else { y };
+    let era = y.div_euclid(400);
+    let yoe = y - era * 400;
+    let doy = (153 * ((m + 9) % 12) + 2) / 5 + d - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+
+    Some(era * 146097 + doe - 719468)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entry(recipe_id: &str, cooked_on: &str) -> CookLogEntry {
+        CookLogEntry {
+            recipe_id: recipe_id.into(),
+            cooked_on: cooked_on.into(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_stats() {
+        let entries = vec![
+            entry("easy-meat-lasagna", "2024-01-05"),
+            entry("minestrone-soup", "2024-03-01"),
+            entry("easy-meat-lasagna", "2024-06-20"),
+            entry("shrimp-scampi", "2024-02-10"),
+        ];
+
+        assert_eq!(
+            most_cooked(&entries, 1),
+            vec![("easy-meat-lasagna".to_string(), 2)]
+        );
+        assert_eq!(
+            not_cooked_since(&entries, "2024-07-01", 90),
+            vec![
+                ("shrimp-scampi".to_string(), "2024-02-10".to_string()),
+                ("minestrone-soup".to_string(), "2024-03-01".to_string()),
+            ]
+        );
+        assert_eq!(day_number("1970-01-01"), Some(0));
+        assert_eq!(day_number("2024-03-01"), Some(19783));
+        assert_eq!(day_number("2024-02-30"), None);
+        assert_eq!(day_number("2024-2-1"), None);
+    }
+}
@@ -0,0 +1,542 @@
+use regex::Regex;
+use reqwest::Url;
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Class, Name, Predicate};
+use std::collections::{HashSet, VecDeque};
+use std::error::Error;
+
+use crate::recipes::fetch::Fetcher;
+use crate::recipes::get_document;
+
+/// Matches post URLs that are a single slug under the site root (optionally under
+/// `/recipe/` or `/recipes/`), which is how WordPress food blogs lay out recipe posts.
+/// Category, tag, author and pagination URLs have more segments and are left out.
+pub const DEFAULT_RECIPE_PATTERN: &str = r"^https?://[^/]+/(?:recipes?/)?[a-z0-9-]+/?$";
+
+/// Limits for `follow_links`.
+///
+/// # Fields
+///
+/// - `max_depth`: How many links away from the start page to descend.
+/// - `max_pages`: The most pages to fetch in total, recipe or not.
+#[derive(Debug, Clone)]
+pub struct FollowOptions {
+    pub max_depth: usize,
+    pub max_pages: usize,
+}
+
+impl Default for FollowOptions {
+    fn default() -> Self {
+        FollowOptions {
+            max_depth: 2,
+            max_pages: 200,
+        }
+    }
+}
+
+/// Limits for `sitemap_urls`.
+///
+/// # Fields
+///
+/// - `pattern`: Only page URLs matching this are returned.
+/// - `max_sitemaps`: The most sitemap files to fetch, counting the one given and every
+///   child of a sitemap index.
+#[derive(Debug, Clone)]
+pub struct SitemapOptions {
+    pub pattern: Regex,
+    pub max_sitemaps: usize,
+}
+
+impl Default for SitemapOptions {
+    fn default() -> Self {
+        SitemapOptions {
+            pattern: Regex::new(DEFAULT_RECIPE_PATTERN).unwrap(),
+            max_sitemaps: 50,
+        }
+    }
+}
+
+/// The contents of one sitemap file.
+#[derive(Debug, PartialEq)]
+pub enum Sitemap {
+    /// A `<sitemapindex>`, listing the URLs of further sitemaps.
+    Index(Vec<String>),
+    /// A `<urlset>`, listing the URLs of pages.
+    Urls(Vec<String>),
+}
+
+/// Parses a sitemap or sitemap index into the URLs of its `<loc>` entries.
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let document = Document::from(xml);
+    let locs = |parent: &str| -> Vec<String> {
+        document
+            .find(Name(parent))
+            .filter_map(|entry| entry.find(Name("loc")).next())
+            .map(|loc| loc.text().trim().to_string())
+            .filter(|loc| !loc.is_empty())
+            .collect()
+    };
+
+    if document.find(Name("sitemapindex")).next().is_some() {
+        Sitemap::Index(locs("sitemap"))
+    } else {
+        Sitemap::Urls(locs("url"))
+    }
+}
+
+/// Classes of the containers WordPress themes put numbered page links in, both for
+/// archives (`page-numbers`) and posts split over several pages (`page-links`).
+const PAGINATION_CLASSES: &[&str] = &[
+    "pagination",
+    "nav-links",
+    "page-links",
+    "page-numbers",
+    "post-page-numbers",
+];
+
+/// Whether a node is, or sits inside, a pagination block.
+fn in_pagination(node: &Node) -> bool {
+    let mut current = Some(*node);
+    while let Some(n) = current {
+        if n.attr("class").is_some_and(|class| {
+            class
+                .split_whitespace()
+                .any(|c| PAGINATION_CLASSES.contains(&c))
+        }) {
+            return true;
+        }
+        current = n.parent();
+    }
+    false
+}
+
+/// Finds the link to the page after this one in a paginated roundup or archive.
+///
+/// Tries, in order: a `rel="next"` link or anchor, an anchor with the `next` class
+/// (WordPress's `a.next.page-numbers`), and the numbered pagination link one past the
+/// current page.
+///
+/// # Returns
+///
+/// - The next page's URL resolved against `base`, if it is on the same host.
+pub fn next_page(document: &Document, base: &Url) -> Option<Url> {
+    let rel_next = document.find(Name("link").or(Name("a"))).find(|n| {
+        n.attr("rel")
+            .is_some_and(|rel| rel.split_whitespace().any(|r| r == "next"))
+    });
+    let class_next = || document.find(Name("a").and(Class("next"))).next();
+    let numbered = || {
+        let current: u32 = document
+            .find(Class("current").or(Attr("aria-current", "page")))
+            .filter(in_pagination)
+            .find_map(|n| n.text().trim().parse().ok())?;
+        document
+            .find(Name("a"))
+            .filter(in_pagination)
+            .find(|a| a.text().trim().parse() == Ok(current + 1))
+    };
+
+    let link = rel_next.or_else(class_next).or_else(numbered)?;
+    let url = base.join(link.attr("href")?).ok()?;
+    (url.host_str() == base.host_str() && &url != base).then_some(url)
+}
+
+/// Collects the post URLs listed on a category or tag archive page: the title link of
+/// each `<article>` (or, failing that, its first link), resolved against `base` and kept
+/// only if on the same host.
+pub fn archive_links(document: &Document, base: &Url) -> Vec<Url> {
+    let mut out = Vec::new();
+
+    for article in document.find(Name("article")) {
+        let Some(href) = article
+            .find(Class("entry-title").or(Name("h2")).or(Name("h3")))
+            .find_map(|title| title.find(Name("a")).next())
+            .or_else(|| article.find(Name("a")).next())
+            .and_then(|a| a.attr("href"))
+        else {
+            continue;
+        };
+        let Ok(mut url) = base.join(href) else {
+            continue;
+        };
+        url.set_fragment(None);
+        url.set_query(None);
+
+        if url.host_str() == base.host_str() && !out.contains(&url) {
+            out.push(url);
+        }
+    }
+
+    out
+}
+
+/// The category or tag an archive URL lists, as its slug: `dinner` for
+/// `/category/dinner/` and `/category/dinner/page/2/`, `pasta` for the subcategory
+/// `/category/dinner/pasta/`.
+pub fn archive_term(url: &Url) -> Option<String> {
+    let segments: Vec<&str> = url.path_segments()?.filter(|s| !s.is_empty()).collect();
+    let start = segments
+        .iter()
+        .position(|s| ["category", "tag", "recipe-category", "recipe-tag"].contains(s))?;
+    let end = segments
+        .iter()
+        .position(|s| *s == "page")
+        .unwrap_or(segments.len());
+
+    segments
+        .get(start + 1..end)?
+        .last()
+        .map(|term| term.to_string())
+}
+
+/// Whether a page carries recipe markup: a Tasty Recipes or WP Recipe Maker card,
+/// schema.org Recipe microdata, or a JSON-LD block mentioning a Recipe.
+pub fn is_recipe_page(document: &Document) -> bool {
+    document.find(Class("tasty-recipes")).next().is_some()
+        || document.find(Class("wprm-recipe")).next().is_some()
+        || document.find(Attr("itemtype", ())).any(|n| {
+            n.attr("itemtype")
+                .is_some_and(|t| t.ends_with("schema.org/Recipe"))
+        })
+        || document
+            .find(Name("script").and(Attr("type", "application/ld+json")))
+            .any(|s| s.text().contains("\"Recipe\""))
+}
+
+/// Collects the links on a page that stay on the same host as `base`, resolved to
+/// absolute URLs without fragments or query strings. Links to uploads, feeds and
+/// non-HTML files are dropped.
+pub fn page_links(document: &Document, base: &Url) -> Vec<Url> {
+    let mut out = Vec::new();
+
+    for href in document.find(Name("a")).filter_map(|a| a.attr("href")) {
+        let Ok(mut url) = base.join(href) else {
+            continue;
+        };
+        url.set_fragment(None);
+        url.set_query(None);
+
+        let path = url.path().to_lowercase();
+        let skipped = path.contains("/wp-content/")
+            || path.split('/').any(|segment| segment == "feed")
+            || path.contains("/wp-json/")
+            || [".jpg", ".jpeg", ".png", ".gif", ".webp", ".pdf", ".xml"]
+                .iter()
+                .any(|ext| path.ends_with(ext));
+
+        if url.host_str() == base.host_str() && !skipped && !out.contains(&url) {
+            out.push(url);
+        }
+    }
+
+    out
+}
+
+/// Discovers recipe pages by following links breadth-first from `start`.
+///
+/// For sites with no usable sitemap or archive pages. Only links on the same host are
+/// followed, never deeper than `options.max_depth` links from the start page, and at
+/// most `options.max_pages` pages are fetched. Pages that fail to load are skipped.
+///
+/// # Returns
+///
+/// - The URLs of the pages classified as recipes, in the order they were found.
+///
+/// # Errors
+///
+/// Returns an error if `start` is not a valid URL.
+pub async fn follow_links(
+    fetcher: &dyn Fetcher,
+    start: &str,
+    options: &FollowOptions,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let start = Url::parse(start)?;
+
+    let mut seen: HashSet<Url> = HashSet::from([start.clone()]);
+    let mut frontier: VecDeque<(Url, usize)> = VecDeque::from([(start, 0)]);
+    let mut fetched = 0;
+    let mut recipes = Vec::new();
+
+    while let Some((url, depth)) = frontier.pop_front() {
+        if fetched >= options.max_pages {
+            break;
+        }
+        fetched += 1;
+
+        let document = match get_document(fetcher, url.as_str()).await {
+            Ok(doc) => doc,
+            Err(e) => {
+                println!("Url: {} Threw the following: {}", url, e);
+                continue;
+            }
+        };
+
+        if is_recipe_page(&document) {
+            recipes.push(url.to_string());
+        }
+
+        if depth < options.max_depth {
+            for link in page_links(&document, &url) {
+                if seen.insert(link.clone()) {
+                    frontier.push_back((link, depth + 1));
+                }
+            }
+        }
+    }
+
+    Ok(recipes)
+}
+
+/// Discovers recipe URLs from a site's sitemap.
+///
+/// Sitemap indexes (as written by Yoast and WordPress core) are followed into their
+/// child sitemaps, up to `options.max_sitemaps` files in total. Child sitemaps that fail
+/// to load are skipped; page URLs are kept if they match `options.pattern`.
+///
+/// # Arguments
+///
+/// - `sitemap`: The URL of the sitemap or sitemap index, usually `/sitemap.xml` or
+///   `/sitemap_index.xml`.
+///
+/// # Returns
+///
+/// - The matching page URLs, in sitemap order and without duplicates.
+///
+/// # Errors
+///
+/// Returns an error if `sitemap` itself cannot be fetched.
+pub async fn sitemap_urls(
+    fetcher: &dyn Fetcher,
+    sitemap: &str,
+    options: &SitemapOptions,
+) -> Result<Vec<String>, Box<dyn Error>> {
+    let mut pending = VecDeque::from([sitemap.to_string()]);
+    let mut seen: HashSet<String> = HashSet::from([sitemap.to_string()]);
+    let mut fetched = 0;
+    let mut urls = Vec::new();
+
+    while let Some(url) = pending.pop_front() {
+        if fetched >= options.max_sitemaps {
+            break;
+        }
+        fetched += 1;
+
+        let xml = match fetcher.fetch(&url).await {
+            Ok(xml) => xml,
+            Err(e) if url == sitemap => return Err(e),
+            Err(e) => {
+                println!("Url: {} Threw the following: {}", url, e);
+                continue;
+            }
+        };
+
+        match parse_sitemap(&xml) {
+            Sitemap::Index(children) => {
+                for child in children {
+                    if seen.insert(child.clone()) {
+                        pending.push_back(child);
+                    }
+                }
+            }
+            Sitemap::Urls(pages) => {
+                for page in pages {
+                    if options.pattern.is_match(&page) && seen.insert(page.clone()) {
+                        urls.push(page);
+                    }
+                }
+            }
+        }
+    }
+
+    Ok(urls)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipes::fetch::tests::FakeFetcher;
+
+    #[test]
+    fn test_page_links() {
+        let document = Document::from(
+            r#"<html><body>
+                <a href="/easy-meat-lasagna/#comments">Lasagna</a>
+                <a href="https://www.aheadofthyme.com/easy-meat-lasagna/">Again</a>
+                <a href="minestrone-soup/?utm_source=x">Soup</a>
+                <a href="https://www.pinterest.com/pin/1">Pin</a>
+                <a href="/wp-content/uploads/lasagna.jpg">Photo</a>
+                <a href="/feed/">RSS</a>
+                <a href="/easy-meat-lasagna/feed">Comments RSS</a>
+                <a href="/feeding-a-crowd/">Feeding a Crowd</a>
+            </body></html>"#,
+        );
+        let base = Url::parse("https://www.aheadofthyme.com/category/recipes/").unwrap();
+
+        let links: Vec<String> = page_links(&document, &base)
+            .iter()
+            .map(|u| u.to_string())
+            .collect();
+
+        assert_eq!(
+            links,
+            vec![
+                "https://www.aheadofthyme.com/easy-meat-lasagna/",
+                "https://www.aheadofthyme.com/category/recipes/minestrone-soup/",
+                "https://www.aheadofthyme.com/feeding-a-crowd/",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_is_recipe_page() {
+        let recipe = Document::from(
+            r#"<script type="application/ld+json">{"@type": "Recipe", "name": "Soup"}</script>"#,
+        );
+        let post = Document::from(r#"<article><p>Our trip to Italy</p></article>"#);
+
+        assert!(is_recipe_page(&recipe));
+        assert!(!is_recipe_page(&post));
+    }
+
+    #[test]
+    fn test_parse_sitemap() {
+        let index = parse_sitemap(
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+            <sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <sitemap><loc>https://www.aheadofthyme.com/post-sitemap.xml</loc></sitemap>
+                <sitemap><loc> https://www.aheadofthyme.com/category-sitemap.xml </loc></sitemap>
+            </sitemapindex>"#,
+        );
+        assert_eq!(
+            index,
+            Sitemap::Index(vec![
+                "https://www.aheadofthyme.com/post-sitemap.xml".to_string(),
+                "https://www.aheadofthyme.com/category-sitemap.xml".to_string(),
+            ])
+        );
+
+        let Sitemap::Urls(urls) = parse_sitemap(
+            r#"<urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <url><loc>https://www.aheadofthyme.com/easy-meat-lasagna/</loc><lastmod>2024-01-01</lastmod></url>
+                <url><loc>https://www.aheadofthyme.com/category/dinner/</loc></url>
+                <url><loc>https://www.aheadofthyme.com/recipes/minestrone-soup</loc></url>
+            </urlset>"#,
+        ) else {
+            panic!("Expected a urlset");
+        };
+
+        let pattern = SitemapOptions::default().pattern;
+        let recipes: Vec<&String> = urls.iter().filter(|u| pattern.is_match(u)).collect();
+        assert_eq!(
+            recipes,
+            vec![
+                "https://www.aheadofthyme.com/easy-meat-lasagna/",
+                "https://www.aheadofthyme.com/recipes/minestrone-soup",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_sitemap_urls() {
+        let fetcher = FakeFetcher::new(&[
+            (
+                "https://www.aheadofthyme.com/sitemap_index.xml",
+                r#"<sitemapindex>
+                    <sitemap><loc>https://www.aheadofthyme.com/post-sitemap.xml</loc></sitemap>
+                    <sitemap><loc>https://www.aheadofthyme.com/missing-sitemap.xml</loc></sitemap>
+                </sitemapindex>"#,
+            ),
+            (
+                "https://www.aheadofthyme.com/post-sitemap.xml",
+                r#"<urlset>
+                    <url><loc>https://www.aheadofthyme.com/easy-meat-lasagna/</loc></url>
+                    <url><loc>https://www.aheadofthyme.com/category/dinner/</loc></url>
+                </urlset>"#,
+            ),
+        ]);
+
+        let urls = sitemap_urls(
+            &fetcher,
+            "https://www.aheadofthyme.com/sitemap_index.xml",
+            &SitemapOptions::default(),
+        )
+        .await
+        .expect("Failed to read sitemap");
+
+        assert_eq!(
+            urls,
+            vec!["https://www.aheadofthyme.com/easy-meat-lasagna/"]
+        );
+        assert_eq!(fetcher.requests.lock().unwrap().len(), 3);
+        assert!(sitemap_urls(
+            &fetcher,
+            "https://www.aheadofthyme.com/missing-sitemap.xml",
+            &SitemapOptions::default()
+        )
+        .await
+        .is_err());
+    }
+
+    #[test]
+    fn test_next_page() {
+        let base = Url::parse("https://www.aheadofthyme.com/category/dinner/").unwrap();
+
+        let rel = Document::from(
+            r#"<head><link rel="next" href="https://www.aheadofthyme.com/category/dinner/page/2/"></head>"#,
+        );
+        assert_eq!(
+            next_page(&rel, &base).map(String::from),
+            Some("https://www.aheadofthyme.com/category/dinner/page/2/".to_string())
+        );
+
+        let numbered = Document::from(
+            r#"<div class="page-links">
+                <a class="post-page-numbers" href="/best-salads/">1</a>
+                <span class="post-page-numbers current" aria-current="page">2</span>
+                <a class="post-page-numbers" href="/best-salads/3/">3</a>
+            </div>"#,
+        );
+        assert_eq!(
+            next_page(&numbered, &base).map(String::from),
+            Some("https://www.aheadofthyme.com/best-salads/3/".to_string())
+        );
+
+        let last = Document::from(
+            r#"<nav class="pagination"><a class="page-numbers" href="/category/dinner/">1</a>
+               <span class="page-numbers current">2</span></nav>"#,
+        );
+        assert!(next_page(&last, &base).is_none());
+    }
+
+    #[test]
+    fn test_archive_links() {
+        let document = Document::from(
+            r#"<main>
+                <article><a href="/easy-meat-lasagna/"><img src="/lasagna.jpg"></a>
+                    <h2 class="entry-title"><a href="/easy-meat-lasagna/">Easy Meat Lasagna</a></h2></article>
+                <article><h2><a href="https://www.aheadofthyme.com/minestrone-soup/#comments">Minestrone</a></h2></article>
+                <article><a href="https://www.pinterest.com/pin/1">Pin</a></article>
+            </main>"#,
+        );
+        let base = Url::parse("https://www.aheadofthyme.com/category/dinner/page/2/").unwrap();
+
+        let links: Vec<String> = archive_links(&document, &base)
+            .iter()
+            .map(|u| u.to_string())
+            .collect();
+        assert_eq!(
+            links,
+            vec![
+                "https://www.aheadofthyme.com/easy-meat-lasagna/",
+                "https://www.aheadofthyme.com/minestrone-soup/",
+            ]
+        );
+
+        assert_eq!(archive_term(&base).as_deref(), Some("dinner"));
+        let sub = Url::parse("https://www.aheadofthyme.com/category/dinner/pasta/").unwrap();
+        assert_eq!(archive_term(&sub).as_deref(), Some("pasta"));
+        let post = Url::parse("https://www.aheadofthyme.com/easy-meat-lasagna/").unwrap();
+        assert_eq!(archive_term(&post), None);
+    }
+}
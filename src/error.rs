@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fmt;
+
+/// A stable code for a class of failure, so reports and retries can group failures by
+/// cause instead of matching on messages.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// The request timed out.
+    #[serde(rename = "E_FETCH_TIMEOUT")]
+    FetchTimeout,
+    /// The server answered with an error status.
+    #[serde(rename = "E_FETCH_STATUS")]
+    FetchStatus,
+    /// Any other network failure (DNS, connection, body read).
+    #[serde(rename = "E_FETCH")]
+    Fetch,
+    /// An element the scraper relies on was not on the page.
+    #[serde(rename = "E_SELECTOR_MISSING")]
+    SelectorMissing,
+    /// The page has no recipe on it, e.g. a post converted to a gallery.
+    #[serde(rename = "E_NOT_A_RECIPE")]
+    NotARecipe,
+    /// The Nutrifox label did not contain the expected recipe data.
+    #[serde(rename = "E_NUTRITION_REGEX")]
+    NutritionRegex,
+    /// A value on the page could not be parsed (JSON, numbers).
+    #[serde(rename = "E_PARSE")]
+    Parse,
+    /// A pre-store hook rejected the recipe.
+    #[serde(rename = "E_HOOK")]
+    Hook,
+    /// Writing to the database failed.
+    #[serde(rename = "E_DB_WRITE")]
+    DbWrite,
+    /// Anything not classified above.
+    #[serde(rename = "E_UNKNOWN")]
+    Unknown,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::FetchTimeout => "E_FETCH_TIMEOUT",
+            ErrorCode::FetchStatus => "E_FETCH_STATUS",
+            ErrorCode::Fetch => "E_FETCH",
+            ErrorCode::SelectorMissing => "E_SELECTOR_MISSING",
+            ErrorCode::NotARecipe => "E_NOT_A_RECIPE",
+            ErrorCode::NutritionRegex => "E_NUTRITION_REGEX",
+            ErrorCode::Parse => "E_PARSE",
+            ErrorCode::Hook => "E_HOOK",
+            ErrorCode::DbWrite => "E_DB_WRITE",
+            ErrorCode::Unknown => "E_UNKNOWN",
+        }
+    }
+
+    /// Classifies any error: a `ScrapeError` keeps its code, and well-known library
+    /// errors (reqwest, serde_json, number parsing) are mapped to the matching code.
+    pub fn of(error: &(dyn Error + 'static)) -> Self {
+        if let Some(e) = error.downcast_ref::<ScrapeError>() {
+            e.code
+        } else if let Some(e) = error.downcast_ref::<reqwest::Error>() {
+            if e.is_timeout() {
+                ErrorCode::FetchTimeout
+            } else if e.is_status() {
+                ErrorCode::FetchStatus
+            } else {
+                ErrorCode::Fetch
+            }
+        } else if error.is::<serde_json::Error>()
+            || error.is::<std::num::ParseFloatError>()
+            || error.is::<std::num::ParseIntError>()
+        {
+            ErrorCode::Parse
+        } else {
+            ErrorCode::Unknown
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// An error carrying a stable `ErrorCode` alongside its message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScrapeError {
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl ScrapeError {
+    pub fn new(code: ErrorCode, message: impl Into<String>) -> Self {
+        ScrapeError {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ScrapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.message)
+    }
+}
+
+impl Error for ScrapeError {}
+
+/// Formats any error as `[CODE] message`, for logs and reports.
+pub fn describe(error: &(dyn Error + 'static)) -> String {
+    format!("[{}] {}", ErrorCode::of(error), error)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_error_code_of() {
+        let e: Box<dyn Error> = Box::new(ScrapeError::new(
+            ErrorCode::SelectorMissing,
+            "No recipe name found",
+        ));
+        assert_eq!(ErrorCode::of(&*e), ErrorCode::SelectorMissing);
+        assert_eq!(describe(&*e), "[E_SELECTOR_MISSING] No recipe name found");
+
+        let e: Box<dyn Error> = "nope".parse::<f32>().unwrap_err().into();
+        assert_eq!(ErrorCode::of(&*e), ErrorCode::Parse);
+
+        assert_eq!(describe(&*e), "[E_PARSE] invalid float literal");
+
+        let e: Box<dyn Error> = "something else".into();
+        assert_eq!(ErrorCode::of(&*e), ErrorCode::Unknown);
+    }
+}
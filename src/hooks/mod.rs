@@ -0,0 +1,141 @@
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+
+use crate::error::{ErrorCode, ScrapeError};
+use crate::recipes::Recipe;
+
+pub type HookResult = Result<(), Box<dyn Error>>;
+
+/// The future returned by a hook, borrowing the recipe it transforms. It is `Send` so
+/// the pipeline can run on any runtime thread.
+pub type HookFuture<'a> = Pin<Box<dyn Future<Output = HookResult> + Send + 'a>>;
+
+type Hook = Box<dyn for<'a> Fn(&'a mut Recipe) -> HookFuture<'a> + Send + Sync>;
+
+/// An ordered set of transforms run on every recipe between parsing and storing it.
+///
+/// Hooks let a deployment tag, enrich or redact recipes without forking the crate,
+/// through the recipe's public fields and setters (`set_notes`, `set_category`, ...).
+/// They run in registration order, and the first failing hook stops the chain so the
+/// recipe is not stored half-transformed.
+///
+/// # Example
+///
+/// ```rust
+/// use data_collection::hooks::Hooks;
+///
+/// let mut hooks = Hooks::new();
+/// hooks.register("uppercase-name", |recipe| {
+///     Box::pin(async move {
+///         recipe.name = recipe.name.to_uppercase();
+///         Ok(())
+///     })
+/// });
+/// ```
+#[derive(Default)]
+pub struct Hooks {
+    hooks: Vec<(String, Hook)>,
+}
+
+impl Hooks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a transform under a name used in error messages.
+    pub fn register<F>(&mut self, name: &str, hook: F)
+    where
+        F: for<'a> Fn(&'a mut Recipe) -> HookFuture<'a> + Send + Sync + 'static,
+    {
+        self.hooks.push((name.to_string(), Box::new(hook)));
+    }
+
+    /// Runs every registered hook on the recipe, in registration order.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first hook error, prefixed with the hook's name.
+    pub async fn run(&self, recipe: &mut Recipe) -> HookResult {
+        for (name, hook) in &self.hooks {
+            if let Err(e) = hook(recipe).await {
+                Err(ScrapeError::new(
+                    ErrorCode::Hook,
+                    format!("Hook {} failed: {}", name, e),
+                ))?
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.hooks.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_run_hooks_in_order() {
+        let mut hooks = Hooks::new();
+        hooks.register("name", |r| {
+            Box::pin(async move {
+                r.name = "Lasagna".into();
+                Ok(())
+            })
+        });
+        hooks.register("suffix", |r| {
+            Box::pin(async move {
+                r.name.push_str(" (tested)");
+                Ok(())
+            })
+        });
+        hooks.register("reject", |_| Box::pin(async move { Err("rejected")? }));
+        hooks.register("never", |r| {
+            Box::pin(async move {
+                r.name.clear();
+                Ok(())
+            })
+        });
+
+        let mut recipe = Recipe::default();
+        let err = hooks.run(&mut recipe).await.unwrap_err();
+
+        assert_eq!(recipe.name, "Lasagna (tested)");
+        assert_eq!(err.to_string(), "Hook reject failed: rejected");
+    }
+
+    #[tokio::test]
+    async fn test_redact_and_tag() {
+        let mut hooks = Hooks::new();
+        hooks.register("redact-notes", |r| {
+            Box::pin(async move {
+                let notes = r.notes().map(|n| n.replace("Aunt Sue's", "[redacted]"));
+                r.set_notes(notes);
+                Ok(())
+            })
+        });
+        hooks.register("tag", |r| {
+            Box::pin(async move {
+                r.add_discovered_under("family");
+                Ok(())
+            })
+        });
+
+        let mut recipe = Recipe::default();
+        recipe.set_notes(Some("Aunt Sue's secret: more garlic.".into()));
+        hooks.run(&mut recipe).await.expect("Hooks failed");
+
+        assert_eq!(recipe.notes(), Some("[redacted] secret: more garlic."));
+        assert_eq!(recipe.discovered_under(), ["family"]);
+    }
+
+    #[test]
+    fn test_hooks_are_send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+        assert_send_sync::<Hooks>();
+    }
+}
@@ -1,3 +1,20 @@
+pub mod app;
+#[cfg(feature = "db")]
+pub mod components;
+#[cfg(feature = "db")]
+pub mod cook_log;
+#[cfg(feature = "db")]
 pub mod db;
+pub mod discovery;
+pub mod error;
+pub mod hooks;
+pub mod openfoodfacts;
+#[cfg(feature = "db")]
+pub mod overlay;
+#[cfg(feature = "db")]
+pub mod pantry;
 pub mod recipes;
+pub mod summary;
+#[cfg(feature = "db")]
+pub mod user_data;
 pub mod utils;
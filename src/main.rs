@@ -1,36 +1,71 @@
+use data_collection::app::{App, Options};
 use data_collection::db;
-use data_collection::recipes::*;
+use data_collection::hooks::Hooks;
+use data_collection::recipes::fetch::HttpFetcher;
+use data_collection::summary::{exit, RunSummary};
+use std::path::Path;
+use std::process::ExitCode;
+
+/// Writes the summary to `--summary-out` if given, and returns its exit code.
+fn finish(summary: RunSummary, summary_out: Option<&Path>) -> ExitCode {
+    if let Some(path) = summary_out {
+        if let Err(e) = summary.write_to(path) {
+            println!("Failed to write summary to {}: {}", path.display(), e);
+        }
+    }
+
+    ExitCode::from(summary.exit_code)
+}
 
 #[tokio::main]
-async fn main() {
-    let db = db::conn().await.expect("Failed to connect to DB: ");
+async fn main() -> ExitCode {
+    let args: Vec<String> = std::env::args().collect();
 
-    let document = match get_document("https://www.aheadofthyme.com/50-best-italian-recipes").await
-    {
-        Ok(doc) => doc,
-        Err(_) => panic!("Cannot get doc!"),
+    let options = match Options::from_args(&args) {
+        Ok(options) => options,
+        Err(e) => {
+            println!("{}", e);
+            return ExitCode::from(exit::CONFIG_ERROR);
+        }
     };
+    let summary_out = options.summary_out.clone();
 
-    let recipes = get_recipes(&document).await;
-
-    for rec in recipes {
-        let mut id = rec.url.clone();
-        id = id
-            .trim_start_matches("https://www.aheadofthyme.com/")
-            .trim_end_matches("/")
-            .to_string();
-
-        println!("WROTE: {}", id);
-
-        let _: Option<Recipe> = match db.create(("recipes", id)).content(rec).await {
-            Ok(res) => {
-                println!("Sucess");
-                res
-            }
-            Err(e) => {
-                println!("Failure: {}", e);
-                None
-            }
-        };
-    }
+    let db = match db::conn().await {
+        Ok(db) => db,
+        Err(e) => {
+            println!("Failed to connect to DB: {}", e);
+            let summary = RunSummary {
+                exit_code: exit::CONFIG_ERROR,
+                ..Default::default()
+            };
+            return finish(summary, summary_out.as_deref());
+        }
+    };
+
+    let fetcher = match HttpFetcher::new(options.rate_limit, options.retry) {
+        Ok(fetcher) => fetcher,
+        Err(e) => {
+            println!("Failed to build HTTP client: {}", e);
+            let summary = RunSummary {
+                exit_code: exit::CONFIG_ERROR,
+                ..Default::default()
+            };
+            return finish(summary, summary_out.as_deref());
+        }
+    };
+
+    // Register pre-store transforms here
+    let hooks = Hooks::new();
+
+    let app = App::new(db, hooks, options, Box::new(fetcher));
+    let summary = match (app.options.sitemap.clone(), app.options.archive.clone()) {
+        (Some(sitemap), _) => app.crawl_sitemap(&sitemap).await,
+        (None, Some(archive)) => app.crawl_archive(&archive).await,
+        (None, None) => {
+            app.crawl("https://www.aheadofthyme.com/50-best-italian-recipes")
+                .await
+        }
+    };
+
+    finish(summary, summary_out.as_deref())
 }
@@ -0,0 +1,209 @@
+use reqwest::{StatusCode, Url};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::error::Error;
+
+use crate::recipes::fetch::Fetcher;
+
+const BASE_URL: &str = "https://world.openfoodfacts.org";
+
+/// Nutrition values for a packaged product, per 100 g.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Nutriments {
+    pub energy_kcal: Option<f64>,
+    pub protein: Option<f64>,
+    pub fat: Option<f64>,
+    pub carbohydrates: Option<f64>,
+    pub sugars: Option<f64>,
+    pub fiber: Option<f64>,
+    pub sodium: Option<f64>,
+}
+
+/// A product resolved from Open Food Facts.
+///
+/// # Fields
+///
+/// - `code`: The product barcode.
+/// - `name`: The product name, if Open Food Facts has one.
+/// - `brands`: The brands listed for the product.
+/// - `allergens`: Allergen tags with the language prefix removed (e.g. `milk`, `gluten`).
+/// - `nutriments`: Nutrition values per 100 g.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Product {
+    pub code: String,
+    pub name: Option<String>,
+    pub brands: Vec<String>,
+    pub allergens: Vec<String>,
+    pub nutriments: Nutriments,
+}
+
+impl Product {
+    /// Builds a `Product` out of an Open Food Facts product object.
+    fn from_json(product: &Value) -> Self {
+        let str_field = |key: &str| {
+            product
+                .get(key)
+                .and_then(Value::as_str)
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+        };
+        let nutriment = |key: &str| {
+            product
+                .get("nutriments")
+                .and_then(|n| n.get(key))
+                .and_then(Value::as_f64)
+        };
+
+        Product {
+            code: str_field("code").unwrap_or_default(),
+            name: str_field("product_name"),
+            brands: str_field("brands")
+                .map(|b| b.split(',').map(|s| s.trim().to_string()).collect())
+                .unwrap_or_default(),
+            allergens: product
+                .get("allergens_tags")
+                .and_then(Value::as_array)
+                .map(|tags| {
+                    tags.iter()
+                        .filter_map(Value::as_str)
+                        .map(|t| t.rsplit(':').next().unwrap_or(t).to_string())
+                        .collect()
+                })
+                .unwrap_or_default(),
+            nutriments: Nutriments {
+                energy_kcal: nutriment("energy-kcal_100g"),
+                protein: nutriment("proteins_100g"),
+                fat: nutriment("fat_100g"),
+                carbohydrates: nutriment("carbohydrates_100g"),
+                sugars: nutriment("sugars_100g"),
+                fiber: nutriment("fiber_100g"),
+                sodium: nutriment("sodium_100g"),
+            },
+        }
+    }
+}
+
+/// Whether a fetch failed because the server answered 404 Not Found.
+fn is_not_found(error: &(dyn Error + 'static)) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+        == Some(StatusCode::NOT_FOUND)
+}
+
+/// Looks up a packaged product by barcode.
+///
+/// # Returns
+///
+/// - `Ok(None)` if Open Food Facts does not know the barcode. It answers those with a
+///   404 and a `"status": 0` body, so both are read as not found.
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the response is not valid JSON.
+pub async fn by_barcode(
+    fetcher: &dyn Fetcher,
+    barcode: &str,
+) -> Result<Option<Product>, Box<dyn Error>> {
+    let url = format!("{}/api/v2/product/{}.json", BASE_URL, barcode.trim());
+    let body = match fetcher.fetch(&url).await {
+        Ok(body) => body,
+        Err(e) if is_not_found(&*e) => return Ok(None),
+        Err(e) => return Err(e),
+    };
+    let json: Value = serde_json::from_str(&body)?;
+
+    if json.get("status").and_then(Value::as_i64) != Some(1) {
+        return Ok(None);
+    }
+
+    Ok(json.get("product").map(Product::from_json))
+}
+
+/// Searches for the best matching packaged product by name, e.g. a brand plus the
+/// generic ingredient name ("Rao's marinara sauce").
+///
+/// # Errors
+///
+/// Returns an error if the request fails or the response is not valid JSON.
+pub async fn search(fetcher: &dyn Fetcher, name: &str) -> Result<Option<Product>, Box<dyn Error>> {
+    let url = Url::parse_with_params(
+        &format!("{}/cgi/search.pl", BASE_URL),
+        &[
+            ("search_terms", name),
+            ("search_simple", "1"),
+            ("json", "1"),
+            ("page_size", "1"),
+        ],
+    )?;
+    let json: Value = serde_json::from_str(&fetcher.fetch(url.as_str()).await?)?;
+
+    Ok(json
+        .get("products")
+        .and_then(Value::as_array)
+        .and_then(|products| products.first())
+        .map(Product::from_json))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipes::fetch::tests::FakeFetcher;
+    use crate::recipes::fetch::FetchFuture;
+
+    /// Answers every request with a 404, as Open Food Facts does for unknown barcodes.
+    struct NotFound;
+
+    impl Fetcher for NotFound {
+        fn fetch<'a>(&'a self, _url: &'a str) -> FetchFuture<'a> {
+            let response = http::Response::builder()
+                .status(StatusCode::NOT_FOUND.as_u16())
+                .body("{\"status\": 0}")
+                .unwrap();
+            let e = reqwest::Response::from(response)
+                .error_for_status()
+                .unwrap_err();
+            Box::pin(async move { Err(e.into()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_by_barcode_unknown() {
+        assert_eq!(by_barcode(&NotFound, "0000000000000").await.unwrap(), None);
+
+        let fetcher = FakeFetcher::new(&[(
+            "https://world.openfoodfacts.org/api/v2/product/0747479000017.json",
+            r#"{"status": 1, "product": {"code": "0747479000017", "product_name": "Marinara Sauce"}}"#,
+        )]);
+        let product = by_barcode(&fetcher, " 0747479000017 ")
+            .await
+            .unwrap()
+            .expect("Expected a product");
+        assert_eq!(product.name.as_deref(), Some("Marinara Sauce"));
+
+        // Other failures are still errors
+        assert!(by_barcode(&fetcher, "123").await.is_err());
+    }
+
+    #[test]
+    fn test_product_from_json() {
+        let json: Value = serde_json::from_str(
+            r#"{
+                "code": "0747479000017",
+                "product_name": "Marinara Sauce",
+                "brands": "Rao's, Rao's Homemade",
+                "allergens_tags": ["en:celery"],
+                "nutriments": {"energy-kcal_100g": 71, "fat_100g": 5.3, "sodium_100g": 0.33}
+            }"#,
+        )
+        .unwrap();
+
+        let product = Product::from_json(&json);
+
+        assert_eq!(product.name.as_deref(), Some("Marinara Sauce"));
+        assert_eq!(product.brands, vec!["Rao's", "Rao's Homemade"]);
+        assert_eq!(product.allergens, vec!["celery"]);
+        assert_eq!(product.nutriments.energy_kcal, Some(71.0));
+        assert_eq!(product.nutriments.protein, None);
+    }
+}
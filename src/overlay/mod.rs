@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use surrealdb::{engine::remote::ws::Client, Surreal};
+
+use crate::utils::parse_iso_date;
+
+const TABLE: &str = "overlays";
+
+/// User-generated data about a recipe.
+///
+/// Overlays live in their own table, keyed by the same ID as the recipe record, so
+/// re-scraping a recipe never overwrites them.
+///
+/// # Fields
+///
+/// - `my_rating`: A personal rating from 1 to 5.
+/// - `cooked_count`: How many times the recipe has been cooked.
+/// - `last_cooked`: The date it was last cooked, as `YYYY-MM-DD`.
+/// - `notes`: Personal notes, separate from the scraped `notes`.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RecipeOverlay {
+    pub my_rating: Option<u8>,
+    #[serde(default)]
+    pub cooked_count: u32,
+    pub last_cooked: Option<String>,
+    pub notes: Option<String>,
+}
+
+impl RecipeOverlay {
+    /// Counts one more cooking on `date` (`YYYY-MM-DD`), keeping the latest date as
+    /// `last_cooked` even when an older cooking is logged late.
+    fn record_cooking(&mut self, date: &str) {
+        self.cooked_count += 1;
+        if self.last_cooked.as_deref().is_none_or(|last| last < date) {
+            self.last_cooked = Some(date.to_string());
+        }
+    }
+}
+
+/// Checks that a personal rating is between 1 and 5.
+fn check_rating(rating: u8) -> Result<(), Box<dyn Error>> {
+    if !(1..=5).contains(&rating) {
+        Err(format!("Rating must be between 1 and 5, got {}", rating))?
+    }
+
+    Ok(())
+}
+
+/// Gets the overlay for a recipe, if the user has added anything to it.
+pub async fn get(
+    db: &Surreal<Client>,
+    recipe_id: &str,
+) -> Result<Option<RecipeOverlay>, surrealdb::Error> {
+    db.select((TABLE, recipe_id)).await
+}
+
+/// Lists every overlay with the ID of the recipe it belongs to.
+pub async fn list(db: &Surreal<Client>) -> Result<Vec<(String, RecipeOverlay)>, surrealdb::Error> {
+    #[derive(Deserialize)]
+    struct Row {
+        recipe_id: String,
+        #[serde(flatten)]
+        overlay: RecipeOverlay,
+    }
+
+    let rows: Vec<Row> = db
+        .query("SELECT *, record::id(id) AS recipe_id FROM type::table($table)")
+        .bind(("table", TABLE))
+        .await?
+        .take(0)?;
+
+    Ok(rows.into_iter().map(|r| (r.recipe_id, r.overlay)).collect())
+}
+
+/// Replaces a recipe's overlay.
+pub async fn put(
+    db: &Surreal<Client>,
+    recipe_id: &str,
+    overlay: RecipeOverlay,
+) -> Result<Option<RecipeOverlay>, surrealdb::Error> {
+    db.upsert((TABLE, recipe_id)).content(overlay).await
+}
+
+/// Loads a recipe's overlay (or an empty one), applies `f`, and stores the result.
+async fn modify(
+    db: &Surreal<Client>,
+    recipe_id: &str,
+    f: impl FnOnce(&mut RecipeOverlay),
+) -> Result<Option<RecipeOverlay>, surrealdb::Error> {
+    let mut overlay = get(db, recipe_id).await?.unwrap_or_default();
+    f(&mut overlay);
+    put(db, recipe_id, overlay).await
+}
+
+/// Sets the personal rating for a recipe.
+///
+/// # Errors
+///
+/// Returns an error if `rating` is not between 1 and 5, or the write fails.
+pub async fn set_rating(
+    db: &Surreal<Client>,
+    recipe_id: &str,
+    rating: u8,
+) -> Result<Option<RecipeOverlay>, Box<dyn Error>> {
+    check_rating(rating)?;
+    Ok(modify(db, recipe_id, |o| o.my_rating = Some(rating)).await?)
+}
+
+/// Sets (or clears, with `None`) the personal notes for a recipe.
+pub async fn set_notes(
+    db: &Surreal<Client>,
+    recipe_id: &str,
+    notes: Option<String>,
+) -> Result<Option<RecipeOverlay>, surrealdb::Error> {
+    modify(db, recipe_id, |o| o.notes = notes).await
+}
+
+/// Records that a recipe was cooked on `date` (`YYYY-MM-DD`).
+///
+/// # Errors
+///
+/// Returns an error if `date` is not a valid `YYYY-MM-DD` date, or the write fails.
+pub async fn mark_cooked(
+    db: &Surreal<Client>,
+    recipe_id: &str,
+    date: &str,
+) -> Result<Option<RecipeOverlay>, Box<dyn Error>> {
+    let date = parse_iso_date(date)?;
+    Ok(modify(db, recipe_id, |o| o.record_cooking(&date)).await?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::conn;
+
+    #[test]
+    fn test_check_rating() {
+        assert!(check_rating(0).is_err());
+        assert!(check_rating(1).is_ok());
+        assert!(check_rating(5).is_ok());
+        assert!(check_rating(6).is_err());
+    }
+
+    #[test]
+    fn test_record_cooking() {
+        let mut overlay = RecipeOverlay::default();
+        overlay.record_cooking("2024-03-01");
+        overlay.record_cooking("2024-06-20");
+        overlay.record_cooking("2024-01-05");
+
+        assert_eq!(overlay.cooked_count, 3);
+        assert_eq!(overlay.last_cooked.as_deref(), Some("2024-06-20"));
+    }
+
+    #[tokio::test]
+    async fn rate_and_cook() {
+        let db = conn().await.expect("Failed to connect to db:");
+        let id = "overlay-test-recipe";
+        put(&db, id, RecipeOverlay::default())
+            .await
+            .expect("Failed to reset overlay");
+
+        set_rating(&db, id, 4).await.expect("Failed to set rating");
+        assert!(set_rating(&db, id, 0).await.is_err());
+        assert!(set_rating(&db, id, 6).await.is_err());
+
+        mark_cooked(&db, id, "2024-06-20")
+            .await
+            .expect("Failed to mark cooked");
+        assert!(mark_cooked(&db, id, "June 21").await.is_err());
+        assert!(mark_cooked(&db, id, "2024-02-30").await.is_err());
+
+        set_notes(&db, id, Some("Less salt".into()))
+            .await
+            .expect("Failed to set notes");
+
+        assert_eq!(
+            get(&db, id).await.expect("Failed to get overlay"),
+            Some(RecipeOverlay {
+                my_rating: Some(4),
+                cooked_count: 1,
+                last_cooked: Some("2024-06-20".into()),
+                notes: Some("Less salt".into()),
+            })
+        );
+    }
+}
@@ -0,0 +1,389 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use surrealdb::{engine::remote::ws::Client, Surreal};
+
+use crate::recipes::{convert_quantity, synonyms, Recipe};
+use crate::utils::parse_iso_date;
+
+const TABLE: &str = "pantry";
+
+/// An item on hand in the pantry.
+///
+/// # Fields
+///
+/// - `name`: The generic ingredient name (e.g. "crushed tomatoes").
+/// - `quantity`: How much is on hand, in `unit`.
+/// - `unit`: The unit of `quantity`, if any (e.g. "cup", "lb").
+/// - `barcode`: The product barcode, for packaged items.
+/// - `expires`: The expiry date as `YYYY-MM-DD`, so dates sort as strings.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct PantryItem {
+    pub name: String,
+    pub quantity: f32,
+    pub unit: Option<String>,
+    pub barcode: Option<String>,
+    pub expires: Option<String>,
+}
+
+impl PantryItem {
+    /// The record ID for the item: its lowercased name with spaces as dashes.
+    pub fn id(&self) -> String {
+        self.name
+            .trim()
+            .to_lowercase()
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join("-")
+    }
+
+    /// Checks the item before it is stored, trimming `expires` to its `YYYY-MM-DD` form.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the name is blank, `quantity` is negative or not a number, or
+    /// `expires` is not a valid `YYYY-MM-DD` date.
+    pub fn validated(mut self) -> Result<Self, Box<dyn Error>> {
+        if self.id().is_empty() {
+            Err("Pantry item has no name")?
+        }
+        if !self.quantity.is_finite() || self.quantity < 0.0 {
+            Err(format!(
+                "Quantity of {} must be zero or more, got {}",
+                self.name, self.quantity
+            ))?
+        }
+        if let Some(expires) = &self.expires {
+            self.expires = Some(parse_iso_date(expires)?);
+        }
+
+        Ok(self)
+    }
+
+    /// Whether this item can stand in for a recipe ingredient of the given name.
+    /// Both names are compared by their canonical synonyms, so "courgette" covers "zucchini".
+    ///
+    /// The item must be the thing the ingredient names, not part of a word or a modifier:
+    /// "rice" covers "jasmine rice" but not "rice vinegar", and "salt" doesn't cover
+    /// "unsalted butter".
+    pub fn covers(&self, ingredient: &str) -> bool {
+        let name = synonyms::normalize(&self.id().replace('-', " "));
+        if name.is_empty() {
+            return false;
+        }
+
+        let re = Regex::new(&format!(
+            r"\b{}(?:e?s)?\b\s*(?:$|[,;(]|\band\b|\bor\b)",
+            regex::escape(&name)
+        ))
+        .unwrap();
+        re.is_match(&synonyms::normalize(ingredient))
+    }
+}
+
+/// A recipe suggested because it uses up pantry items that are about to expire.
+#[derive(Debug)]
+pub struct Suggestion<'a> {
+    pub recipe: &'a Recipe,
+    pub uses: Vec<&'a PantryItem>,
+}
+
+impl Suggestion<'_> {
+    /// How much of `item`'s stock the recipe uses up, from 0 to 1: the amount its
+    /// ingredients call for, converted to the item's unit, over the amount on hand.
+    /// An item whose amount can't be compared (no quantity, or units that don't
+    /// convert) counts as used up, so it falls back to counting items.
+    fn share_used(&self, item: &PantryItem) -> f32 {
+        let mut used = None;
+        for (_, quantity, unit) in self
+            .recipe
+            .ingredient_amounts()
+            .filter(|(name, _, _)| item.covers(name))
+        {
+            let amount = match (unit, item.unit.as_deref()) {
+                (Some(from), Some(to)) => convert_quantity(quantity, from, to),
+                (None, None) => Some(quantity),
+                _ => None,
+            };
+            if let Some(amount) = amount.filter(|_| quantity > 0.0) {
+                used = Some(used.unwrap_or(0.0) + amount);
+            }
+        }
+
+        match used {
+            Some(used) if item.quantity > 0.0 => (used / item.quantity).min(1.0),
+            _ => 1.0,
+        }
+    }
+
+    /// How much expiring stock this recipe uses up, in items' worth (see `share_used`).
+    fn stock_used(&self) -> f32 {
+        self.uses.iter().map(|item| self.share_used(item)).sum()
+    }
+
+    /// The earliest expiry date among the items this recipe uses.
+    fn soonest(&self) -> Option<&str> {
+        self.uses.iter().filter_map(|i| i.expires.as_deref()).min()
+    }
+}
+
+/// Suggests recipes that use up pantry items expiring on or before `cutoff`.
+///
+/// Recipes are ranked by how much of the expiring stock they use up (see
+/// `Suggestion::share_used`), then by how many expiring items they use, then by the
+/// soonest expiry among those items. Recipes that use no expiring items are left out,
+/// as are items whose expiry is not a valid date. An invalid `cutoff` suggests nothing.
+///
+/// # Arguments
+/// - `recipes`: The candidate recipes.
+/// - `pantry`: The items on hand.
+/// - `cutoff`: The last expiry date (`YYYY-MM-DD`) that counts as expiring soon.
+pub fn suggest_expiring<'a>(
+    recipes: &'a [Recipe],
+    pantry: &'a [PantryItem],
+    cutoff: &str,
+) -> Vec<Suggestion<'a>> {
+    let Ok(cutoff) = parse_iso_date(cutoff) else {
+        return Vec::new();
+    };
+
+    // Valid dates compare correctly as strings
+    let expiring: Vec<&PantryItem> = pantry
+        .iter()
+        .filter(|item| {
+            item.expires
+                .as_deref()
+                .and_then(|e| parse_iso_date(e).ok())
+                .is_some_and(|e| e <= cutoff)
+        })
+        .collect();
+
+    let mut suggestions: Vec<Suggestion> = recipes
+        .iter()
+        .map(|recipe| Suggestion {
+            recipe,
+            uses: expiring
+                .iter()
+                .filter(|item| recipe.ingredient_names().any(|name| item.covers(name)))
+                .copied()
+                .collect(),
+        })
+        .filter(|s| !s.uses.is_empty())
+        .collect();
+
+    suggestions.sort_by(|a, b| {
+        b.stock_used()
+            .total_cmp(&a.stock_used())
+            .then_with(|| b.uses.len().cmp(&a.uses.len()))
+            .then_with(|| a.soonest().cmp(&b.soonest()))
+    });
+
+    suggestions
+}
+
+/// Adds an item to the pantry, replacing any item with the same name.
+///
+/// # Errors
+///
+/// Returns an error if the item fails `PantryItem::validated`, or the write fails.
+pub async fn upsert(
+    db: &Surreal<Client>,
+    item: PantryItem,
+) -> Result<Option<PantryItem>, Box<dyn Error>> {
+    let item = item.validated()?;
+    Ok(db.upsert((TABLE, item.id())).content(item).await?)
+}
+
+/// Gets a single pantry item by its ID.
+pub async fn get(db: &Surreal<Client>, id: &str) -> Result<Option<PantryItem>, surrealdb::Error> {
+    db.select((TABLE, id)).await
+}
+
+/// Lists everything in the pantry.
+pub async fn list(db: &Surreal<Client>) -> Result<Vec<PantryItem>, surrealdb::Error> {
+    db.select(TABLE).await
+}
+
+/// Removes an item from the pantry, returning it if it existed.
+pub async fn remove(
+    db: &Surreal<Client>,
+    id: &str,
+) -> Result<Option<PantryItem>, surrealdb::Error> {
+    db.delete((TABLE, id)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_id_and_covers() {
+        let item = PantryItem {
+            name: "Crushed  Tomatoes".into(),
+            quantity: 2.0,
+            ..Default::default()
+        };
+
+        assert_eq!(item.id(), "crushed-tomatoes");
+        assert!(item.covers("canned crushed tomatoes"));
+        assert!(!item.covers("tomato paste"));
+
+        let courgette = PantryItem {
+            name: "Courgette".into(),
+            ..Default::default()
+        };
+        assert!(courgette.covers("2 zucchini, grated"));
+
+        let salt = PantryItem {
+            name: "Salt".into(),
+            ..Default::default()
+        };
+        assert!(salt.covers("kosher salt"));
+        assert!(salt.covers("salt and pepper"));
+        assert!(!salt.covers("unsalted butter"));
+
+        let rice = PantryItem {
+            name: "Rice".into(),
+            ..Default::default()
+        };
+        assert!(rice.covers("jasmine rice"));
+        assert!(!rice.covers("rice vinegar"));
+    }
+
+    /// A recipe from `(name, quantity, unit)` ingredients, with units as `Unit` variants.
+    fn recipe(name: &str, ingredients: &[(&str, f32, Option<&str>)]) -> Recipe {
+        let mut json = serde_json::to_value(Recipe::default()).unwrap();
+        json["name"] = name.into();
+        json["ingredients"] = ingredients
+            .iter()
+            .map(|(name, quantity, units)| {
+                serde_json::json!({ "name": name, "quantity": quantity, "units": units })
+            })
+            .collect();
+        serde_json::from_value(json).expect("Failed to build recipe")
+    }
+
+    fn item(name: &str, quantity: f32, expires: &str) -> PantryItem {
+        PantryItem {
+            name: name.into(),
+            quantity,
+            expires: Some(expires.into()),
+            ..Default::default()
+        }
+    }
+
+    fn measured(name: &str, quantity: f32, unit: &str, expires: &str) -> PantryItem {
+        PantryItem {
+            unit: Some(unit.into()),
+            ..item(name, quantity, expires)
+        }
+    }
+
+    #[test]
+    fn test_validated() {
+        let valid = item("Spinach", 1.0, " 2024-06-03 ").validated().unwrap();
+        assert_eq!(valid.expires.as_deref(), Some("2024-06-03"));
+
+        assert!(item("Spinach", 1.0, "6/3/2024").validated().is_err());
+        assert!(item("Spinach", 1.0, "2024-02-30").validated().is_err());
+        assert!(PantryItem::default().validated().is_err());
+    }
+
+    #[test]
+    fn test_validated_quantity() {
+        assert!(item("Spinach", 0.0, "2024-06-03").validated().is_ok());
+        assert!(item("Spinach", 0.25, "2024-06-03").validated().is_ok());
+        assert!(item("Spinach", -1.0, "2024-06-03").validated().is_err());
+        assert!(item("Spinach", f32::NAN, "2024-06-03").validated().is_err());
+        assert!(item("Spinach", f32::INFINITY, "2024-06-03")
+            .validated()
+            .is_err());
+        assert!(item("   ", 1.0, "2024-06-03").validated().is_err());
+    }
+
+    #[tokio::test]
+    async fn add_update_remove() {
+        let db = crate::db::conn().await.expect("Failed to connect to db:");
+        let id = "pantry-test-flour";
+
+        let added = upsert(&db, item("Pantry  test flour", 5.0, "2025-01-31"))
+            .await
+            .expect("Failed to add item");
+        assert_eq!(added.map(|i| i.quantity), Some(5.0));
+
+        // The same name, in any case or spacing, updates the existing item
+        let updated = upsert(&db, item("pantry test FLOUR", 0.0, "2025-02-28"))
+            .await
+            .expect("Failed to update item")
+            .expect("No item returned");
+        assert_eq!(updated.quantity, 0.0);
+        assert_eq!(
+            get(&db, id).await.expect("Failed to get item"),
+            Some(updated.clone())
+        );
+        assert_eq!(
+            list(&db)
+                .await
+                .expect("Failed to list items")
+                .iter()
+                .filter(|i| i.id() == id)
+                .count(),
+            1
+        );
+
+        // Invalid items never reach the table
+        assert!(upsert(&db, item("Pantry test flour", -2.0, "2025-02-28"))
+            .await
+            .is_err());
+        assert!(upsert(&db, item("Pantry test flour", 1.0, "02/28/2025"))
+            .await
+            .is_err());
+        assert_eq!(get(&db, id).await.unwrap(), Some(updated.clone()));
+
+        assert_eq!(
+            remove(&db, id).await.expect("Failed to remove item"),
+            Some(updated)
+        );
+        assert_eq!(get(&db, id).await.unwrap(), None);
+        assert_eq!(remove(&db, id).await.unwrap(), None);
+    }
+
+    #[test]
+    fn test_suggest_expiring() {
+        let recipes = vec![
+            recipe("Spinach Salad", &[("baby spinach", 0.5, Some("LB"))]),
+            recipe("Spinach Pie", &[("frozen spinach", 2.0, Some("LB"))]),
+            recipe(
+                "Caprese",
+                &[
+                    ("tomatoes", 4.0, None),
+                    ("fresh basil", 2.0, Some("TABLESPOON")),
+                ],
+            ),
+            // No unit to compare with the cups on hand, so the basil counts as used up
+            recipe("Pesto", &[("fresh basil", 2.0, None)]),
+            recipe("Plain Rice", &[("rice", 1.0, Some("CUP"))]),
+        ];
+        let pantry = vec![
+            measured("Spinach", 2.0, "lb", "2024-06-03"),
+            measured("Basil", 1.0, "cup", "2024-06-01"),
+            item("Tomatoes", 4.0, "2024-06-02"),
+            measured("Cream cheese", 1.0, "lb", "2024-07-15"),
+            measured("Rice", 5.0, "cups", "sometime"),
+        ];
+
+        let suggestions = suggest_expiring(&recipes, &pantry, "2024-06-05");
+        let names: Vec<&str> = suggestions.iter().map(|s| s.recipe.name.as_str()).collect();
+
+        // All the tomatoes and an eighth of the basil, then all of one item (the basil
+        // expiring first), then a quarter of the spinach
+        assert_eq!(
+            names,
+            vec!["Caprese", "Pesto", "Spinach Pie", "Spinach Salad"]
+        );
+        assert_eq!(suggestions[0].stock_used(), 1.125);
+        assert_eq!(suggestions[3].uses, vec![&pantry[0]]);
+
+        assert!(suggest_expiring(&recipes, &pantry, "next week").is_empty());
+    }
+}
@@ -0,0 +1,77 @@
+use super::Instruction;
+
+/// How many leading words of a step go into its anchor. Edits past these (fixing a
+/// typo at the end, adding a tip) keep the anchor.
+const KEY_WORDS: usize = 6;
+
+/// 32-bit FNV-1a, used instead of `DefaultHasher` because anchors are stored and must
+/// not change between Rust versions.
+fn fnv1a(bytes: &[u8]) -> u32 {
+    bytes.iter().fold(0x811c_9dc5, |hash, b| {
+        (hash ^ *b as u32).wrapping_mul(0x0100_0193)
+    })
+}
+
+/// The anchor for step `index` (zero-based) of a section, e.g. `step-3-9f1c2a7e`.
+///
+/// Hashes the section name, the index and the first `KEY_WORDS` words of the step,
+/// lowercased with punctuation dropped, so case, punctuation and late edits don't move it.
+pub fn anchor(section: Option<&str>, index: usize, step: &str) -> String {
+    let words: Vec<String> = step
+        .split_whitespace()
+        .map(|w| {
+            w.chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+                .to_lowercase()
+        })
+        .filter(|w| !w.is_empty())
+        .take(KEY_WORDS)
+        .collect();
+
+    let key = format!(
+        "{}|{}|{}",
+        section.unwrap_or_default().trim().to_lowercase(),
+        index,
+        words.join(" ")
+    );
+
+    format!("step-{}-{:08x}", index + 1, fnv1a(key.as_bytes()))
+}
+
+impl Instruction {
+    /// Sets one anchor per step, for deep-linking to a specific step.
+    pub(super) fn assign_anchors(&mut self) {
+        self.anchors = self
+            .steps
+            .iter()
+            .enumerate()
+            .map(|(i, step)| anchor(self.section.as_deref(), i, step))
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_anchor() {
+        let a = anchor(Some("Sauce"), 0, "Heat the olive oil in a large pot.");
+
+        assert!(a.starts_with("step-1-"));
+        assert_eq!(
+            a,
+            anchor(
+                Some("sauce"),
+                0,
+                "Heat the olive oil, in a large pot over medium heat"
+            )
+        );
+        assert_ne!(a, anchor(None, 0, "Heat the olive oil in a large pot."));
+        assert_ne!(
+            a,
+            anchor(Some("Sauce"), 1, "Heat the olive oil in a large pot.")
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use regex::Regex;
+
+/// Brands that show up in ingredient lists without a trademark marker. A capitalised
+/// possessive alone is not enough, since "Grandma's meatballs" names no brand.
+const KNOWN_BRANDS: &[&str] = &[
+    "Barilla",
+    "Better Than Bouillon",
+    "Bob's Red Mill",
+    "Campbell's",
+    "De Cecco",
+    "Diamond Crystal",
+    "Frank's RedHot",
+    "Ghirardelli",
+    "Goya",
+    "Heinz",
+    "Hellmann's",
+    "Hunt's",
+    "Kerrygold",
+    "Kewpie",
+    "Kikkoman",
+    "King Arthur",
+    "Kraft",
+    "Land O'Lakes",
+    "Lee Kum Kee",
+    "McCormick",
+    "Mike's",
+    "Morton",
+    "Muir Glen",
+    "Mutti",
+    "Old Bay",
+    "Philadelphia",
+    "Rao's",
+    "Swanson",
+    "Trader Joe's",
+];
+
+/// Separates a brand from a scraped ingredient name.
+///
+/// Brands are recognised from a list of common grocery brands (`Rao's marinara sauce`)
+/// or a trademark symbol (`Kraft® Parmesan`).
+///
+/// # Arguments
+/// - `name`: The ingredient name as scraped.
+///
+/// # Returns
+/// - A tuple of the brand, if one was found, and the generic ingredient name.
+pub fn split_brand(name: &str) -> (Option<String>, String) {
+    let name = name.trim();
+
+    // Known brands are listed with straight apostrophes
+    let straight = name.replace('’', "'");
+    for brand in KNOWN_BRANDS {
+        if let Some(rest) = strip_prefix_ignore_case(&straight, brand) {
+            let rest = rest.trim_start_matches(['®', '™']).trim();
+            if !rest.is_empty() {
+                return (Some(brand.to_string()), rest.to_string());
+            }
+        }
+    }
+
+    let marked_re = Regex::new(r"^(.+?)\s*[®™]\s*(.+)$").unwrap();
+    if let Some(caps) = marked_re.captures(name) {
+        return (Some(caps[1].to_string()), caps[2].to_string());
+    }
+
+    (None, name.to_string())
+}
+
+fn strip_prefix_ignore_case<'a>(name: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = name.get(..prefix.len())?;
+    let rest = &name[prefix.len()..];
+
+    if head.eq_ignore_ascii_case(prefix) && !rest.starts_with(char::is_alphanumeric) {
+        Some(rest)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_brand() {
+        assert_eq!(
+            split_brand("Rao's marinara sauce"),
+            (Some("Rao's".into()), "marinara sauce".into())
+        );
+        assert_eq!(
+            split_brand("Kraft® grated parmesan"),
+            (Some("Kraft".into()), "grated parmesan".into())
+        );
+        assert_eq!(
+            split_brand("Mike’s Hot Honey"),
+            (Some("Mike's".into()), "Hot Honey".into())
+        );
+        assert_eq!(split_brand("kosher salt"), (None, "kosher salt".into()));
+        assert_eq!(split_brand("Philadelphia"), (None, "Philadelphia".into()));
+    }
+
+    #[test]
+    fn test_split_brand_possessive_is_not_a_brand() {
+        assert_eq!(
+            split_brand("Grandma's meatballs"),
+            (None, "Grandma's meatballs".into())
+        );
+        assert_eq!(
+            split_brand("Trader's spice blend"),
+            (None, "Trader's spice blend".into())
+        );
+        assert_eq!(
+            split_brand("Trader Joe's everything bagel seasoning"),
+            (
+                Some("Trader Joe's".into()),
+                "everything bagel seasoning".into()
+            )
+        );
+    }
+}
@@ -0,0 +1,253 @@
+use reqwest::{Client, StatusCode, Url};
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+
+use super::retry::{self, RetryPolicy};
+use super::throttle::{self, RateLimit, Throttle};
+
+/// How many times a request is retried after a host answers 429 Too Many Requests.
+const MAX_RATE_LIMITED_RETRIES: u32 = 3;
+
+/// The future returned by `Fetcher::fetch`, resolving to the body of the page.
+pub type FetchFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<String, Box<dyn Error>>> + Send + 'a>>;
+
+/// Fetches pages for discovery and scraping.
+///
+/// A run builds one fetcher and passes it down the pipeline, so every request shares
+/// the same client and per-host state. Tests pass a fetcher serving fixture pages.
+pub trait Fetcher: Send + Sync {
+    /// Fetches the body of the page at `url`.
+    fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a>;
+}
+
+/// Fetches pages over HTTP with a single reused `reqwest::Client`.
+///
+/// Requests to the same host are spaced out by the fetcher's `RateLimit`, so a crawl
+/// doesn't fetch every recipe (and its nutrition label) back-to-back. When a host
+/// answers 429, every request to that host is paused for its Retry-After period (see
+/// `throttle`) and the request is retried, up to `MAX_RATE_LIMITED_RETRIES` times,
+/// rather than failing the recipe.
+///
+/// Timeouts, connection failures, server errors and bodies cut off mid-read are retried
+/// with exponential backoff under the fetcher's `RetryPolicy`. Other error statuses, such as 404, are
+/// permanent and fail straight away.
+pub struct HttpFetcher {
+    client: Client,
+    throttle: Throttle,
+    retry: RetryPolicy,
+}
+
+impl HttpFetcher {
+    /// Builds the client, with the request timeout of `retry`.
+    ///
+    /// # Arguments
+    ///
+    /// - `rate_limit`: How fast requests to any one host may be sent.
+    /// - `retry`: How failed requests are retried.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the HTTP client cannot be initialised (e.g. no TLS backend).
+    pub fn new(rate_limit: RateLimit, retry: RetryPolicy) -> Result<Self, reqwest::Error> {
+        Ok(HttpFetcher {
+            client: Client::builder().timeout(retry.timeout).build()?,
+            throttle: Throttle::new(rate_limit),
+            retry,
+        })
+    }
+
+    async fn get(&self, url: &str) -> Result<String, reqwest::Error> {
+        let policy = &self.retry;
+        let host = Url::parse(url)
+            .ok()
+            .and_then(|u| u.host_str().map(String::from))
+            .unwrap_or_default();
+
+        let mut retries = 0;
+        let mut failures = 0;
+        loop {
+            self.throttle.wait(&host).await;
+            let res = match self.client.get(url).send().await {
+                Ok(res) => res,
+                Err(e) if retry::is_retryable(&e) && failures + 1 < policy.max_attempts => {
+                    failures += 1;
+                    println!("Retrying {} ({}): {}", url, failures, e);
+                    tokio::time::sleep(policy.delay(failures)).await;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            if res.status() == StatusCode::TOO_MANY_REQUESTS {
+                self.throttle
+                    .pause(&host, throttle::retry_after(res.headers()));
+
+                if retries < MAX_RATE_LIMITED_RETRIES {
+                    retries += 1;
+                    continue;
+                }
+            } else if retry::is_retryable_status(res.status()) && failures + 1 < policy.max_attempts
+            {
+                failures += 1;
+                println!("Retrying {} ({}): {}", url, failures, res.status());
+                tokio::time::sleep(policy.delay(failures)).await;
+                continue;
+            }
+
+            res.error_for_status_ref()?;
+
+            // The connection can still drop while the body is read
+            match res.text().await {
+                Ok(body) => return Ok(body),
+                Err(e) if failures + 1 < policy.max_attempts => {
+                    failures += 1;
+                    println!("Retrying {} ({}): {}", url, failures, e);
+                    tokio::time::sleep(policy.delay(failures)).await;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+}
+
+impl Fetcher for HttpFetcher {
+    fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a> {
+        Box::pin(async move { Ok(self.get(url).await?) })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    use crate::error::ErrorCode;
+
+    /// Serves fixture pages by URL, and records every URL it is asked for.
+    #[derive(Default)]
+    pub(crate) struct FakeFetcher {
+        pages: HashMap<String, String>,
+        pub requests: Mutex<Vec<String>>,
+    }
+
+    impl FakeFetcher {
+        pub fn new(pages: &[(&str, &str)]) -> Self {
+            FakeFetcher {
+                pages: pages
+                    .iter()
+                    .map(|(url, body)| (url.to_string(), body.to_string()))
+                    .collect(),
+                ..Default::default()
+            }
+        }
+    }
+
+    impl Fetcher for FakeFetcher {
+        fn fetch<'a>(&'a self, url: &'a str) -> FetchFuture<'a> {
+            self.requests.lock().unwrap().push(url.to_string());
+            let page = self.pages.get(url).cloned();
+            Box::pin(async move { page.ok_or_else(|| format!("No fixture for {}", url).into()) })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fake_fetcher() {
+        let fetcher = FakeFetcher::new(&[("https://example.com/", "<html></html>")]);
+
+        assert_eq!(
+            fetcher.fetch("https://example.com/").await.unwrap(),
+            "<html></html>"
+        );
+        assert!(fetcher.fetch("https://example.com/missing/").await.is_err());
+        assert_eq!(fetcher.requests.lock().unwrap().len(), 2);
+    }
+
+    /// Serves `responses` (raw HTTP) in order, one per connection, on a local port.
+    /// Returns the server's URL and a count of the requests it has answered.
+    async fn serve(responses: &[&str]) -> (String, Arc<Mutex<usize>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}/", listener.local_addr().unwrap());
+        let served = Arc::new(Mutex::new(0));
+
+        let responses: Vec<String> = responses.iter().map(|r| r.to_string()).collect();
+        let count = served.clone();
+        tokio::spawn(async move {
+            for response in responses {
+                let (mut socket, _) = listener.accept().await.unwrap();
+                let mut request = Vec::new();
+                let mut buf = [0; 1024];
+                while !request.ends_with(b"\r\n\r\n") {
+                    let n = socket.read(&mut buf).await.unwrap();
+                    if n == 0 {
+                        break;
+                    }
+                    request.extend_from_slice(&buf[..n]);
+                }
+                socket.write_all(response.as_bytes()).await.unwrap();
+                socket.shutdown().await.unwrap();
+                *count.lock().unwrap() += 1;
+            }
+        });
+
+        (url, served)
+    }
+
+    fn response(status: &str, headers: &str, body: &str) -> String {
+        format!(
+            "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+            status,
+            body.len(),
+            headers,
+            body
+        )
+    }
+
+    fn fetcher(max_attempts: u32) -> HttpFetcher {
+        let rate_limit = RateLimit {
+            rps: 0.0,
+            jitter: Duration::ZERO,
+        };
+        let retry = RetryPolicy {
+            max_attempts,
+            base_delay: Duration::from_millis(1),
+            max_delay: Duration::from_millis(1),
+            timeout: Duration::from_secs(5),
+        };
+        HttpFetcher::new(rate_limit, retry).expect("Failed to build client")
+    }
+
+    #[tokio::test]
+    async fn test_http_fetcher_retries() {
+        let (unavailable, limited, ok) = (
+            response("503 Service Unavailable", "", ""),
+            response("429 Too Many Requests", "Retry-After: 0\r\n", ""),
+            response("200 OK", "", "<html></html>"),
+        );
+        // Promises 100 bytes but hangs up after 5
+        let cut_off = "HTTP/1.1 200 OK\r\nContent-Length: 100\r\nConnection: close\r\n\r\nshort";
+
+        let (url, served) = serve(&[&unavailable, &limited, cut_off, &ok]).await;
+        assert_eq!(fetcher(3).fetch(&url).await.unwrap(), "<html></html>");
+        assert_eq!(*served.lock().unwrap(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_http_fetcher_gives_up() {
+        let error = response("500 Internal Server Error", "", "");
+        let (url, served) = serve(&[&error, &error, &error]).await;
+        let e = fetcher(2).fetch(&url).await.unwrap_err();
+        assert_eq!(ErrorCode::of(&*e), ErrorCode::FetchStatus);
+        assert_eq!(*served.lock().unwrap(), 2);
+
+        let missing = response("404 Not Found", "", "");
+        let (url, served) = serve(&[&missing, &missing]).await;
+        assert!(fetcher(3).fetch(&url).await.is_err());
+        assert_eq!(*served.lock().unwrap(), 1);
+    }
+}
@@ -0,0 +1,223 @@
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Class, Name, Predicate};
+use std::error::Error;
+
+use super::selectors::Match;
+use super::{brand, sub_recipe, Ingredient, Instruction, Macros, Recipe};
+use crate::error::{ErrorCode, ScrapeError};
+use crate::utils::normalize_text;
+
+/// Confidence given to every field found by the heuristic extractor. Recipes parsed
+/// this way should be reviewed before they are trusted.
+pub const HEURISTIC_CONFIDENCE: f32 = 0.3;
+
+/// How many lists after a heading are considered when picking the largest one.
+const LISTS_PER_HEADING: usize = 3;
+
+/// Finds the first heading whose text contains one of `words`, then returns the list
+/// (of the given tag names) with the most items among the first few that follow it.
+fn list_after<'a>(document: &'a Document, words: &[&str], tags: &[&str]) -> Option<Node<'a>> {
+    let heading = document
+        .find(
+            Name("h2")
+                .or(Name("h3"))
+                .or(Name("h4"))
+                .or(Name("strong"))
+                .or(Name("p")),
+        )
+        .find(|h| {
+            let text = h.text().to_lowercase();
+            text.len() < 60 && words.iter().any(|w| text.contains(w))
+        })?;
+
+    document
+        .find(|n: &Node| n.name().is_some_and(|name| tags.contains(&name)))
+        .filter(|list| list.index() > heading.index())
+        .take(LISTS_PER_HEADING)
+        .max_by_key(|list| list.find(Name("li")).count())
+        .filter(|list| list.find(Name("li")).next().is_some())
+}
+
+/// Classes used by gallery and slideshow plugins.
+const GALLERY_CLASSES: &[&str] = &[
+    "gallery",
+    "wp-block-gallery",
+    "slideshow",
+    "tiled-gallery",
+    "swiper-wrapper",
+];
+
+/// Whether the page is an image gallery or slideshow, as happens when a recipe post is
+/// turned into a roundup. Only meaningful once no recipe was found on the page.
+pub(super) fn is_gallery(document: &Document) -> bool {
+    GALLERY_CLASSES
+        .iter()
+        .any(|class| document.find(Class(*class)).next().is_some())
+        || document.find(Attr("data-slide", ())).next().is_some()
+}
+
+fn heuristic_match<'a>(node: Node<'a>, selector: &str) -> Match<'a> {
+    Match {
+        node,
+        selector: selector.to_string(),
+        confidence: HEURISTIC_CONFIDENCE,
+    }
+}
+
+impl Recipe {
+    /// Last-resort parser for pages with no structured data or known plugin markup.
+    ///
+    /// Takes the name from the first `<h1>` (or `og:title`), the ingredients from the
+    /// largest `<ul>` near a heading mentioning "Ingredients", and the steps from the
+    /// largest `<ol>` near a heading mentioning "Instructions", "Directions" or "Method".
+    /// A paragraph listing calories and other nutrients is parsed into macros if present.
+    /// Every field gets `HEURISTIC_CONFIDENCE`, so the result is flagged as low confidence.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no ingredient list or no instruction list can be found.
+    pub(super) fn parse_heuristic(&mut self, document: &Document) -> Result<(), Box<dyn Error>> {
+        let ingredients =
+            list_after(document, &["ingredient"], &["ul", "ol"]).ok_or_else(|| {
+                ScrapeError::new(
+                    ErrorCode::SelectorMissing,
+                    format!("No ingredient list found for: {}", self.url),
+                )
+            })?;
+        let instructions = list_after(
+            document,
+            &["instruction", "direction", "method", "steps"],
+            &["ol", "ul"],
+        )
+        .filter(|list| list.index() != ingredients.index())
+        .ok_or_else(|| {
+            ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!("No instruction list found for: {}", self.url),
+            )
+        })?;
+
+        if let Some(h1) = document.find(Name("h1")).next() {
+            self.name = h1.text().trim().to_string();
+            self.trace
+                .record_match("name", &heuristic_match(h1, "h1"), Some(&self.name), None);
+        } else if let Some(title) = document
+            .find(Name("meta").and(Attr("property", "og:title")))
+            .next()
+        {
+            self.name = title.attr("content").unwrap_or_default().trim().to_string();
+            self.trace.record_match(
+                "name",
+                &heuristic_match(title, "meta[property='og:title']"),
+                Some(&self.name),
+                None,
+            );
+        }
+
+        let mut parsed = Vec::new();
+        for li in ingredients.find(Name("li")) {
+            let raw = li.text().trim().to_string();
+            let text = normalize_text(&raw);
+            if text.is_empty() {
+                continue;
+            }
+
+            let (brand, name) = brand::split_brand(&text);
+            let (optional, to_taste) = Ingredient::flags(&text);
+            parsed.push(Ingredient {
+                name,
+                brand,
+                quantity: 0.0,
+                units: None,
+                prepped: None,
+                optional,
+                to_taste,
+                sub_recipe: sub_recipe::link(&li, &self.url),
+                raw,
+            });
+        }
+        self.trace.record_match(
+            "ingredients",
+            &heuristic_match(ingredients, "largest list after an \"Ingredients\" heading"),
+            None,
+            Some(format!("{} ingredients", parsed.len())),
+        );
+        self.add_ingredients(parsed);
+
+        let steps: Vec<String> = instructions
+            .find(Name("li"))
+            .map(|li| li.text().trim().to_string())
+            .filter(|step| !step.is_empty())
+            .collect();
+        self.trace.record_match(
+            "instructions",
+            &heuristic_match(
+                instructions,
+                "largest list after an \"Instructions\" heading",
+            ),
+            None,
+            Some(format!("{} steps", steps.len())),
+        );
+        self.instructions = vec![Instruction {
+            section: None,
+            steps,
+            anchors: Vec::new(),
+        }];
+
+        if let Some((p, macros)) = document
+            .find(Name("p").or(Name("ul")))
+            .filter(|p| p.text().to_lowercase().contains("calories"))
+            .find_map(|p| Macros::from_text(&p.text()).map(|m| (p, m)))
+        {
+            self.macros = Some(macros);
+            self.trace.record_match(
+                "macros",
+                &heuristic_match(p, "paragraph mentioning \"Calories\""),
+                Some(&p.text()),
+                Some("Macros::from_text".to_string()),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_heuristic() {
+        let document = Document::from(
+            r#"<html><body>
+                <h1>Grandma's Tomato Soup</h1>
+                <ul><li>Home</li><li>Recipes</li></ul>
+                <h3>Ingredients</h3>
+                <ul><li>2 cans crushed tomatoes</li><li>1 onion</li><li>Rao's marinara sauce</li></ul>
+                <h3>Directions</h3>
+                <ol><li>Sweat the onion.</li><li>Add the tomatoes and simmer 20 minutes.</li></ol>
+            </body></html>"#,
+        );
+
+        let mut r = Recipe::default();
+        r.parse_heuristic(&document)
+            .expect("Failed to parse heuristically");
+
+        assert_eq!(r.name, "Grandma's Tomato Soup");
+        assert_eq!(r.ingredients.len(), 3);
+        assert_eq!(r.ingredients[2].brand.as_deref(), Some("Rao's"));
+        assert_eq!(r.ingredients[2].raw, "Rao's marinara sauce");
+        assert_eq!(r.instructions[0].steps.len(), 2);
+        assert_eq!(r.trace.confidence(), Some(HEURISTIC_CONFIDENCE));
+
+        let empty = Document::from("<html><body><p>No recipe here</p></body></html>");
+        assert!(Recipe::default().parse_heuristic(&empty).is_err());
+        assert!(!is_gallery(&empty));
+
+        let gallery = Document::from(
+            r#"<html><body><div class="wp-block-gallery"><figure><img src="1.jpg"></figure></div></body></html>"#,
+        );
+        assert!(is_gallery(&gallery));
+    }
+}
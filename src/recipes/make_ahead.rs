@@ -0,0 +1,193 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::storage::StorageInfo;
+use super::Instruction;
+
+/// Meal-prep flags detected from a recipe's notes and steps.
+///
+/// # Fields
+///
+/// - `make_ahead`: Whether the recipe (or a component of it) can be prepared in advance.
+/// - `freezer_friendly`: Whether the recipe can be frozen.
+/// - `lead_time`: How far ahead it can be made, in minutes, when the text says so.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct MakeAhead {
+    #[serde(default)]
+    pub make_ahead: bool,
+    #[serde(default)]
+    pub freezer_friendly: bool,
+    pub lead_time: Option<u32>,
+}
+
+impl MakeAhead {
+    /// Detects make-ahead and freezer guidance in the notes and instruction steps.
+    ///
+    /// A recipe with a freezer duration in its `storage` info is always freezer friendly.
+    /// Otherwise the text has to say the dish keeps frozen ("freezes well", "can be
+    /// frozen", "freeze for up to 3 months"); cooking with the freezer or frozen food
+    /// ("add the frozen peas") does not count. Phrases like "does not freeze well" veto
+    /// the flag.
+    ///
+    /// A lead time has to be "ahead" or "in advance"; "2 hours before" only counts after
+    /// a make-ahead cue ("make", "prepare", "assemble", "up to"), since on its own it is
+    /// usually a wait between steps ("rest 2 hours before rolling").
+    ///
+    /// # Arguments
+    /// - `notes`: The cleaned notes text, if any.
+    /// - `instructions`: The parsed instruction sections.
+    /// - `storage`: Storage info previously extracted from the notes.
+    pub fn detect(
+        notes: Option<&str>,
+        instructions: &[Instruction],
+        storage: Option<&StorageInfo>,
+    ) -> Self {
+        let ahead_re = Regex::new(
+            r"(?i)make[- ]ahead|ahead of time|in advance|\bday ahead|the (?:day|night) before",
+        )
+        .unwrap();
+        let lead_re =
+            Regex::new(r"(?i)(\d+)\s*(hour|hr|day|week)s?\s*(ahead|in advance|before)").unwrap();
+        let cue_re =
+            Regex::new(r"(?i)\b(?:make|made|prep(?:are|ared)?|assembled?|up to)\b[^.;]*$").unwrap();
+        let freeze_re = Regex::new(
+            r"(?i)\bfreezes\s+(?:really\s+|very\s+)?(?:well|beautifully|great|nicely|perfectly)|\bfreezable\b|\bfreezer[- ]friendly\b|\b(?:can|could|may|will)\s+(?:also\s+|easily\s+)?be\s+frozen\b|\bto freeze\b|\bfreez(?:e|er)\b[^.;]*?\b\d+\s*(?:day|week|month)s?\b",
+        )
+        .unwrap();
+        let no_freeze_re = Regex::new(
+            r"(?i)(?:not|n't|never)\s+(?:recommend\s+)?freez|(?:not|n't)\s+freeze\s+well",
+        )
+        .unwrap();
+
+        let mut texts: Vec<&str> = notes.into_iter().collect();
+        for instruction in instructions {
+            texts.extend(instruction.steps.iter().map(String::as_str));
+        }
+
+        let mut out = MakeAhead {
+            freezer_friendly: storage.is_some_and(|s| s.freezer_days.is_some()),
+            ..Default::default()
+        };
+        let mut vetoed = false;
+
+        for text in texts {
+            if ahead_re.is_match(text) {
+                out.make_ahead = true;
+            }
+
+            let leads = lead_re.captures_iter(text).filter(|caps| {
+                !caps[3].eq_ignore_ascii_case("before")
+                    || cue_re.is_match(&text[..caps.get(0).unwrap().start()])
+            });
+            for caps in leads {
+                out.make_ahead = true;
+
+                let amount = caps[1].parse::<u32>().unwrap_or(0);
+                let minutes = match caps[2].to_lowercase().as_str() {
+                    "day" => amount.checked_mul(24 * 60),
+                    "week" => amount.checked_mul(7 * 24 * 60),
+                    _ => amount.checked_mul(60),
+                };
+                // A lead time too long to count in minutes is dropped, not guessed at
+                if let Some(minutes) = minutes {
+                    out.lead_time = Some(out.lead_time.map_or(minutes, |t| t.max(minutes)));
+                }
+            }
+
+            if no_freeze_re.is_match(text) {
+                vetoed = true;
+            } else if freeze_re.is_match(text) {
+                out.freezer_friendly = true;
+            }
+        }
+
+        if vetoed {
+            out.freezer_friendly = false;
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect() {
+        let instructions = vec![Instruction {
+            section: None,
+            steps: vec!["Assemble the lasagna up to 2 days in advance and refrigerate.".into()],
+            anchors: Vec::new(),
+        }];
+
+        let flags = MakeAhead::detect(Some("Freezes beautifully."), &instructions, None);
+
+        assert!(flags.make_ahead);
+        assert!(flags.freezer_friendly);
+        assert_eq!(flags.lead_time, Some(2 * 24 * 60));
+
+        let flags = MakeAhead::detect(Some("This salad does not freeze well."), &[], None);
+        assert_eq!(flags, MakeAhead::default());
+    }
+
+    #[test]
+    fn test_detect_freezer_phrasing() {
+        for notes in [
+            "Freezes well for a busy week.",
+            "The sauce can be frozen.",
+            "Freeze for up to 3 months.",
+            "To freeze, cool completely and wrap tightly.",
+        ] {
+            assert!(
+                MakeAhead::detect(Some(notes), &[], None).freezer_friendly,
+                "{}",
+                notes
+            );
+        }
+
+        for notes in [
+            "Stir in the frozen peas.",
+            "Chill in the freezer 10 minutes so the dough firms up.",
+            "Thaw the frozen spinach and squeeze it dry.",
+        ] {
+            assert!(
+                !MakeAhead::detect(Some(notes), &[], None).freezer_friendly,
+                "{}",
+                notes
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_before() {
+        let flags = MakeAhead::detect(
+            Some("Make the dough up to 2 days before baking."),
+            &[],
+            None,
+        );
+        assert!(flags.make_ahead);
+        assert_eq!(flags.lead_time, Some(2 * 24 * 60));
+
+        for notes in [
+            "Let the dough rest 2 hours before rolling.",
+            "Chill 1 hour before slicing.",
+            "Marinate for 3 days before grilling.",
+        ] {
+            assert_eq!(
+                MakeAhead::detect(Some(notes), &[], None),
+                MakeAhead::default(),
+                "{}",
+                notes
+            );
+        }
+    }
+
+    #[test]
+    fn test_detect_lead_time_overflow() {
+        let flags = MakeAhead::detect(Some("Make up to 1000000 weeks ahead."), &[], None);
+
+        assert!(flags.make_ahead);
+        assert_eq!(flags.lead_time, None);
+    }
+}
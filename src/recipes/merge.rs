@@ -0,0 +1,143 @@
+use std::error::Error;
+
+use super::Recipe;
+
+/// How a re-scraped recipe is reconciled with the version already stored.
+///
+/// User data (ratings, notes, cook counts) lives in overlays and is never touched by
+/// either policy; this only decides between two scraped versions.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum ConflictPolicy {
+    /// The new scrape replaces the stored recipe outright.
+    PreferNewest,
+    /// The stored recipe is kept; the new scrape is discarded.
+    PreferExisting,
+    /// Fields refresh from the new scrape, but anything it came back without is kept
+    /// from the stored recipe, and differing descriptions are combined.
+    #[default]
+    Merge,
+}
+
+impl ConflictPolicy {
+    pub fn from(str: &str) -> Result<Self, Box<dyn Error>> {
+        match str.to_lowercase().as_str() {
+            "prefer-newest" | "newest" => Ok(ConflictPolicy::PreferNewest),
+            "prefer-existing" | "existing" => Ok(ConflictPolicy::PreferExisting),
+            "merge" => Ok(ConflictPolicy::Merge),
+            _ => Err(format!("Unknown conflict policy: {}", str))?,
+        }
+    }
+}
+
+/// Keeps `existing` in `field` if the scrape came back without a value.
+fn keep_if_missing<T>(field: &mut Option<T>, existing: Option<T>) {
+    if field.is_none() {
+        *field = existing;
+    }
+}
+
+fn keep_if_empty<T>(field: &mut Vec<T>, existing: Vec<T>) {
+    if field.is_empty() {
+        *field = existing;
+    }
+}
+
+fn keep_if_blank(field: &mut String, existing: String) {
+    if field.trim().is_empty() {
+        *field = existing;
+    }
+}
+
+fn keep_if_zero<T: Default + PartialEq>(field: &mut T, existing: T) {
+    if *field == T::default() {
+        *field = existing;
+    }
+}
+
+impl Recipe {
+    /// Reconciles this freshly scraped recipe with the `existing` stored one.
+    pub fn resolve(mut self, existing: Recipe, policy: ConflictPolicy) -> Recipe {
+        match policy {
+            ConflictPolicy::PreferNewest => return self,
+            ConflictPolicy::PreferExisting => return existing,
+            ConflictPolicy::Merge => {}
+        }
+
+        self.description = match (self.description.take(), existing.description) {
+            (Some(new), Some(old)) if !new.contains(old.trim()) && !old.contains(new.trim()) => {
+                Some(format!("{}\n\n{}", old.trim(), new.trim()))
+            }
+            (Some(new), Some(old)) if old.len() > new.len() => Some(old),
+            (new, old) => new.or(old),
+        };
+
+        keep_if_blank(&mut self.img, existing.img);
+        keep_if_blank(&mut self.img_alt, existing.img_alt);
+        keep_if_blank(&mut self.cuisine, existing.cuisine);
+        keep_if_blank(&mut self.category, existing.category);
+        keep_if_blank(&mut self.method, existing.method);
+        keep_if_blank(&mut self.name, existing.name);
+        keep_if_zero(&mut self.total_time, existing.total_time);
+        keep_if_zero(&mut self.prep_time, existing.prep_time);
+        keep_if_zero(&mut self.cook_time, existing.cook_time);
+        keep_if_zero(&mut self.servings, existing.servings);
+        keep_if_empty(&mut self.ingredients, existing.ingredients);
+        keep_if_empty(&mut self.instructions, existing.instructions);
+        keep_if_empty(&mut self.equiptment, existing.equiptment);
+        keep_if_missing(&mut self.video, existing.video);
+        keep_if_missing(&mut self.notes, existing.notes);
+        keep_if_missing(&mut self.storage, existing.storage);
+        keep_if_missing(&mut self.macros, existing.macros);
+
+        // Archives found on earlier crawls still list the recipe
+        let mut terms = existing.discovered_under;
+        for term in self.discovered_under.drain(..) {
+            if !terms.contains(&term) {
+                terms.push(term);
+            }
+        }
+        self.discovered_under = terms;
+
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_merge() {
+        let existing = Recipe {
+            name: "Easy Meat Lasagna".into(),
+            description: Some("A family favourite.".into()),
+            video: Some("https://youtu.be/lasagna".into()),
+            total_time: 90,
+            discovered_under: vec!["dinner".into(), "pasta".into()],
+            ..Default::default()
+        };
+        let scraped = Recipe {
+            name: "Easy Meat Lasagna".into(),
+            description: Some("Ready in 90 minutes.".into()),
+            total_time: 95,
+            discovered_under: vec!["italian".into(), "pasta".into()],
+            ..Default::default()
+        };
+
+        let merged = scraped
+            .clone()
+            .resolve(existing.clone(), ConflictPolicy::Merge);
+        assert_eq!(merged.total_time, 95);
+        assert_eq!(merged.video.as_deref(), Some("https://youtu.be/lasagna"));
+        assert_eq!(
+            merged.description.as_deref(),
+            Some("A family favourite.\n\nReady in 90 minutes.")
+        );
+        assert_eq!(merged.discovered_under, vec!["dinner", "pasta", "italian"]);
+
+        let newest = scraped.resolve(existing, ConflictPolicy::PreferNewest);
+        assert_eq!(newest.video, None);
+
+        assert!(ConflictPolicy::from("bogus").is_err());
+    }
+}
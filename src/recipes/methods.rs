@@ -0,0 +1,130 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::Instruction;
+
+/// A cooking method detected in a recipe's steps.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "kebab-case")]
+pub enum Method {
+    Baked,
+    Roasted,
+    Grilled,
+    Fried,
+    AirFried,
+    Sauteed,
+    Boiled,
+    Steamed,
+    PressureCooked,
+    SlowCooked,
+    NoCook,
+}
+
+/// Keyword patterns per method, matched against lowercased steps in which appliance
+/// phrases have already been collapsed into single tokens (see `classify`).
+const PATTERNS: &[(Method, &str)] = &[
+    (Method::AirFried, r"\bairfry"),
+    (Method::PressureCooked, r"\bpressurecook"),
+    (Method::SlowCooked, r"\bslowcook"),
+    (
+        Method::Baked,
+        r"\bbake[sd]?\b|\bbaking\b|\bpreheat (?:the )?oven\b",
+    ),
+    (Method::Roasted, r"\broast(?:s|ed|ing)?\b"),
+    (
+        Method::Grilled,
+        r"\bgrill(?:s|ed|ing)?\b|\bbarbecue\b|\bbbq\b",
+    ),
+    (Method::Fried, r"\bfry\b|\bfries\b|\bfried\b|\bfrying\b"),
+    (
+        Method::Sauteed,
+        r"\bsaut[eé](?:s|ed|ing)?\b|\bstirfry|\bsear(?:s|ed|ing)?\b",
+    ),
+    (
+        Method::Boiled,
+        r"\bboil(?:s|ed|ing)?\b|\bsimmer(?:s|ed|ing)?\b",
+    ),
+    (Method::Steamed, r"\bsteam(?:s|ed|ing)?\b"),
+];
+
+/// Classifies the cooking methods used across all instruction steps.
+///
+/// Appliance phrases ("air fryer", "Instant Pot", "crock pot") are normalized first so
+/// that an air-fried recipe is not also reported as fried. A recipe with steps but no
+/// heat-based method is reported as `NoCook`.
+///
+/// # Arguments
+/// - `instructions`: The parsed instruction sections.
+///
+/// # Returns
+/// - The detected methods, sorted and without duplicates.
+pub fn classify(instructions: &[Instruction]) -> Vec<Method> {
+    let steps: Vec<&String> = instructions.iter().flat_map(|i| &i.steps).collect();
+    if steps.is_empty() {
+        return Vec::new();
+    }
+
+    let mut text = steps
+        .iter()
+        .map(|s| s.to_lowercase())
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    for (phrase, token) in [
+        (r"air[- ]?fr(?:y|ier|yer)", "airfry"),
+        (r"instant pot|pressure[- ]cook(?:er)?", "pressurecook"),
+        (r"slow[- ]cook(?:er)?|crock[- ]?pot", "slowcook"),
+        (r"stir[- ]fr(?:y|ied|ying)", "stirfry"),
+    ] {
+        text = Regex::new(phrase)
+            .unwrap()
+            .replace_all(&text, token)
+            .to_string();
+    }
+
+    let mut methods: Vec<Method> = PATTERNS
+        .iter()
+        .filter(|(_, pattern)| Regex::new(pattern).unwrap().is_match(&text))
+        .map(|(method, _)| *method)
+        .collect();
+
+    if methods.is_empty() {
+        methods.push(Method::NoCook);
+    }
+
+    methods.sort();
+    methods
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn steps(steps: &[&str]) -> Vec<Instruction> {
+        vec![Instruction {
+            section: None,
+            steps: steps.iter().map(|s| s.to_string()).collect(),
+            anchors: Vec::new(),
+        }]
+    }
+
+    #[test]
+    fn test_classify() {
+        assert_eq!(
+            classify(&steps(&[
+                "Preheat the oven to 375°F.",
+                "Boil the noodles until al dente."
+            ])),
+            vec![Method::Baked, Method::Boiled]
+        );
+        assert_eq!(
+            classify(&steps(&["Air fry the wings at 400°F for 20 minutes."])),
+            vec![Method::AirFried]
+        );
+        assert_eq!(
+            classify(&steps(&["Toss the greens with the dressing and serve."])),
+            vec![Method::NoCook]
+        );
+        assert!(classify(&[]).is_empty());
+    }
+}
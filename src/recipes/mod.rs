@@ -1,22 +1,48 @@
 use regex::Regex;
-use reqwest::Client;
+use reqwest::Url;
 use select::document::Document;
 use select::node::Node;
-use select::predicate::{Attr, Class, Name, Predicate};
+use select::predicate::{Class, Name, Predicate};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::error::Error;
-use std::panic;
 
-use crate::utils::U32Ext;
-
-pub async fn fetch_data(url: &str) -> Result<String, reqwest::Error> {
-    let client = Client::new();
-    let res = client.get(url).send().await?;
-
-    let body = res.text().await?;
-    Ok(body)
-}
+use crate::discovery;
+use crate::error::{self, ErrorCode, ScrapeError};
+use crate::summary::Failure;
+use crate::utils::TimeFormat;
+
+pub mod anchors;
+pub mod brand;
+pub mod fetch;
+pub mod heuristic;
+pub mod make_ahead;
+pub mod merge;
+pub mod methods;
+pub mod nutrition;
+pub mod retry;
+pub mod scrapers;
+pub mod selectors;
+pub mod spelling;
+pub mod spice;
+pub mod storage;
+pub mod sub_recipe;
+pub mod synonyms;
+pub mod throttle;
+pub mod trace;
+pub mod variants;
+
+use fetch::Fetcher;
+use make_ahead::MakeAhead;
+use methods::Method;
+use scrapers::Registry;
+use spice::SpiceLevel;
+use storage::StorageInfo;
+use trace::ParseTrace;
+use variants::RecipeVariant;
+
+/// The most pages of one roundup or archive followed through its pagination links.
+const MAX_LISTING_PAGES: usize = 25;
 
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 struct Nutrient {
@@ -26,9 +52,64 @@ struct Nutrient {
     daily: f64,
 }
 
+/// Where a recipe's nutrition numbers came from.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum NutritionSource {
+    /// The Nutrifox label embedded on the recipe page.
+    Nutrifox,
+    /// The `nutrition` object of a schema.org JSON-LD recipe.
+    JsonLd,
+    /// The `nutrition` item of a schema.org microdata recipe.
+    Microdata,
+    /// A plain-text "Nutrition" paragraph on the recipe page.
+    Text,
+    /// Estimated from the ingredient list (e.g. against FDC data).
+    #[default]
+    Estimated,
+}
+
+/// How far consumers should trust a recipe's nutrition numbers.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum Confidence {
+    #[default]
+    Low,
+    Medium,
+    High,
+}
+
+impl NutritionSource {
+    /// The confidence a source gets by default. Nutrifox labels are computed by the
+    /// author from the exact recipe, structured data and text paragraphs are author-provided but
+    /// often incomplete, and estimates are only as good as the ingredient matching.
+    pub fn confidence(&self) -> Confidence {
+        match self {
+            NutritionSource::Nutrifox => Confidence::High,
+            NutritionSource::JsonLd | NutritionSource::Microdata | NutritionSource::Text => {
+                Confidence::Medium
+            }
+            NutritionSource::Estimated => Confidence::Low,
+        }
+    }
+}
+
+/// Every macros record stored before the source was recorded came from Nutrifox.
+fn legacy_source() -> NutritionSource {
+    NutritionSource::Nutrifox
+}
+
+fn legacy_confidence() -> Confidence {
+    legacy_source().confidence()
+}
+
 #[derive(Serialize, Deserialize, Debug, Default, Clone)]
 #[allow(non_snake_case)]
 struct Macros {
+    #[serde(default = "legacy_source")]
+    source: NutritionSource,
+    #[serde(default = "legacy_confidence")]
+    confidence: Confidence,
     PROCNT: Nutrient,
     FAT: Nutrient,
     CHOCDF: Nutrient,
@@ -122,20 +203,72 @@ impl Unit {
             _ => Err("Error building Unit enum!")?,
         }
     }
+
+    /// The unit's name, as `from` reads it.
+    fn name(&self) -> &'static str {
+        match self {
+            Unit::TABLESPOON => "tablespoon",
+            Unit::TEASPOON => "teaspoon",
+            Unit::CUP => "cup",
+            Unit::LB => "lb",
+            Unit::CONTAINER => "container",
+        }
+    }
+
+    /// What the unit measures, and how many of the smallest unit of that kind it holds.
+    fn size(&self) -> (&'static str, f32) {
+        match self {
+            Unit::TEASPOON => ("volume", 1.0),
+            Unit::TABLESPOON => ("volume", 3.0),
+            Unit::CUP => ("volume", 48.0),
+            Unit::LB => ("weight", 1.0),
+            Unit::CONTAINER => ("count", 1.0),
+        }
+    }
+}
+
+/// Converts `quantity` between two unit names ("3 teaspoons" to "tablespoon"), as long
+/// as both are units `Unit::from` knows and they measure the same thing.
+pub fn convert_quantity(quantity: f32, from: &str, to: &str) -> Option<f32> {
+    let (from_kind, from_size) = Unit::from(from).ok()?.size();
+    let (to_kind, to_size) = Unit::from(to).ok()?.size();
+
+    (from_kind == to_kind).then_some(quantity * from_size / to_size)
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct Instruction {
     pub section: Option<String>,
     pub steps: Vec<String>,
+    /// Stable IDs for deep-linking to each step, parallel to `steps`.
+    #[serde(default)]
+    pub anchors: Vec<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, Clone)]
 struct Ingredient {
     name: String,
+    brand: Option<String>,
     quantity: f32,
     units: Option<Unit>,
     prepped: Option<String>,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    to_taste: bool,
+    sub_recipe: Option<String>,
+    /// The ingredient line exactly as it appeared on the page, so it can be re-parsed.
+    #[serde(default)]
+    raw: String,
+}
+
+impl Ingredient {
+    /// Reads the `(optional)` and `to taste` markers from a full ingredient line,
+    /// returned as `(optional, to_taste)`.
+    fn flags(line: &str) -> (bool, bool) {
+        let line = line.to_lowercase();
+        (line.contains("optional"), line.contains("to taste"))
+    }
 }
 
 /// Represents a recipe with detailed information including image, URL, cuisine type,
@@ -149,10 +282,12 @@ struct Ingredient {
 /// # Fields
 ///
 /// - `img`: A string containing the URL of the recipe's image.
+/// - `img_alt`: The image's alt text from the source page, or a generated description when the source has none.
 /// - `url`: A string containing the URL to the recipe's page.
 /// - `cuisine`: A string indicating the cuisine type of the recipe (e.g., Italian, Mexican).
 /// - `category`: A string indicating the category of the recipe (e.g., dessert, main course).
 /// - `method`: A string describing the method of preparation (e.g., baking, frying).
+/// - `methods`: The cooking methods detected in the instruction steps, since `method` is often blank or generic.
 /// - `total_time`: Total time in minutes for making the recipe.
 /// - `prep_time`: Preparation time in minutes (time spent on getting ingredients ready).
 /// - `cook_time`: Cooking time in minutes.
@@ -160,16 +295,28 @@ struct Ingredient {
 /// - `description`: An optional string providing a description of the recipe.
 /// - `instructions`: A vector of `Instruction` objects detailing the step-by-step process to make the recipe.
 /// - `ingredients`: A vector of `Ingredient` objects containing all the ingredients required for the recipe.
+/// - `variants`: Alternate instructions for specific appliances (Instant Pot, slow cooker, air fryer).
 /// - `video`: An optional string containing the URL to a video tutorial for the recipe.
 /// - `notes`: An optional string containing additional notes for the recipe (e.g., tips or variations).
+/// - `storage`: Optional leftovers guidance (fridge/freezer durations, reheating) extracted from the notes.
+/// - `prep_ahead`: Make-ahead and freezer-friendly flags, serialized inline as `make_ahead`,
+///   `freezer_friendly` and `lead_time`.
 /// - `servings`: The number of servings the recipe yields.
+/// - `spice_level`: The estimated heat of the recipe, derived from its chili-related ingredients.
 /// - `equiptment`: A vector of strings listing the equipment needed for the recipe.
-/// - `macros`: An optional `Macros` object containing nutritional information (e.g., calories, protein).
+/// - `discovered_under`: The category and tag archives (as slugs) the recipe was found
+///   in while crawling, accumulated across runs.
+/// - `parse_confidence`: The lowest confidence among the selectors the fields were parsed with.
+/// - `trace`: A record of which selector each field was parsed from. Not serialized; see
+///   `ParseTrace::write_to`.
+/// - `macros`: An optional `Macros` object containing nutritional information (e.g., calories, protein),
+///   tagged with the `NutritionSource` it came from and a `Confidence` level.
 ///
 /// # Example
 ///
 /// ```rust
-/// let recipe = Recipe::new("https://example.com/image.jpg", "https://example.com/recipe-page")
+/// let fetcher = HttpFetcher::new(RateLimit::default(), RetryPolicy::default()).expect("Failed to build client");
+/// let recipe = Recipe::new(&fetcher, "https://example.com/image.jpg", "https://example.com/recipe-page")
 ///     .expect("Failed to create recipe");
 /// ```
 ///
@@ -181,10 +328,14 @@ struct Ingredient {
 #[derive(Debug, Default, Serialize, Deserialize, Clone)]
 pub struct Recipe {
     img: String,
+    #[serde(default)]
+    img_alt: String,
     pub url: String,
     cuisine: String,
     category: String,
     method: String,
+    #[serde(default)]
+    methods: Vec<Method>,
     total_time: u32,
     prep_time: u32,
     cook_time: u32,
@@ -192,14 +343,131 @@ pub struct Recipe {
     description: Option<String>,
     pub instructions: Vec<Instruction>,
     ingredients: Vec<Ingredient>,
+    #[serde(default)]
+    variants: Vec<RecipeVariant>,
     video: Option<String>,
     notes: Option<String>,
+    storage: Option<StorageInfo>,
+    #[serde(flatten)]
+    prep_ahead: MakeAhead,
     servings: u64,
+    #[serde(default)]
+    spice_level: SpiceLevel,
     equiptment: Vec<String>,
+    #[serde(default)]
+    discovered_under: Vec<String>,
     macros: Option<Macros>,
+    parse_confidence: Option<f32>,
+    #[serde(skip)]
+    pub trace: ParseTrace,
 }
 
 impl Recipe {
+    /// The category and tag archives the recipe was discovered under.
+    pub fn discovered_under(&self) -> &[String] {
+        &self.discovered_under
+    }
+
+    /// Records another category or tag for the recipe, such as one added by a hook.
+    pub fn add_discovered_under(&mut self, term: &str) {
+        if !self.discovered_under.iter().any(|t| t == term) {
+            self.discovered_under.push(term.to_string());
+        }
+    }
+
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+
+    pub fn set_description(&mut self, description: Option<String>) {
+        self.description = description;
+    }
+
+    pub fn notes(&self) -> Option<&str> {
+        self.notes.as_deref()
+    }
+
+    /// Replaces the notes. Fields derived from the notes when the recipe was parsed,
+    /// such as `storage`, are left as they are.
+    pub fn set_notes(&mut self, notes: Option<String>) {
+        self.notes = notes;
+    }
+
+    pub fn cuisine(&self) -> &str {
+        &self.cuisine
+    }
+
+    pub fn set_cuisine(&mut self, cuisine: &str) {
+        self.cuisine = cuisine.to_string();
+    }
+
+    pub fn category(&self) -> &str {
+        &self.category
+    }
+
+    pub fn set_category(&mut self, category: &str) {
+        self.category = category.to_string();
+    }
+
+    /// The names of the recipe's ingredients, for hooks that rename or redact them.
+    pub fn ingredient_names_mut(&mut self) -> impl Iterator<Item = &mut String> {
+        self.ingredients.iter_mut().map(|i| &mut i.name)
+    }
+
+    /// The names of the recipe's ingredients, in list order.
+    pub fn ingredient_names(&self) -> impl Iterator<Item = &str> {
+        self.ingredients.iter().map(|i| i.name.as_str())
+    }
+
+    /// Each ingredient's name with its quantity (zero when none is given) and the name
+    /// of its unit, if it has one.
+    pub fn ingredient_amounts(&self) -> impl Iterator<Item = (&str, f32, Option<&'static str>)> {
+        self.ingredients.iter().map(|i| {
+            (
+                i.name.as_str(),
+                i.quantity,
+                i.units.as_ref().map(Unit::name),
+            )
+        })
+    }
+
+    /// The total, prep and cook times formatted for display, skipping any that are unknown.
+    pub fn formatted_times(&self, format: TimeFormat) -> Vec<(&'static str, String)> {
+        [
+            ("Total", self.total_time),
+            ("Prep", self.prep_time),
+            ("Cook", self.cook_time),
+        ]
+        .into_iter()
+        .filter(|(_, minutes)| *minutes > 0)
+        .map(|(label, minutes)| (label, format.format(minutes)))
+        .collect()
+    }
+
+    /// Each ingredient's name with the URL of the sub-recipe it links to, if any.
+    pub fn components(&self) -> impl DoubleEndedIterator<Item = (&str, Option<&str>)> {
+        self.ingredients
+            .iter()
+            .map(|i| (i.name.as_str(), i.sub_recipe.as_deref()))
+    }
+
+    /// The URLs of other recipes this one uses as ingredients ("1 batch pizza dough"),
+    /// for scraping and linking them.
+    pub fn sub_recipes(&self) -> impl Iterator<Item = &str> {
+        self.ingredients
+            .iter()
+            .filter_map(|i| i.sub_recipe.as_deref())
+    }
+
+    /// Whether any ingredient matches `query`, under any of its synonyms
+    /// (a search for "coriander leaves" finds "chopped cilantro").
+    pub fn has_ingredient(&self, query: &str) -> bool {
+        let terms = synonyms::expand(query);
+        self.ingredient_names()
+            .map(synonyms::normalize)
+            .any(|name| terms.iter().any(|t| name.contains(t.as_str())))
+    }
+
     /// Loads a new recipe from the database
     pub fn from_id(_id: u64) -> Self {
         Recipe {
@@ -215,6 +483,7 @@ impl Recipe {
     ///
     /// # Arguments
     ///
+    /// - `fetcher`: Fetches the recipe page and any nutrition label it embeds.
     /// - `img`: A string slice containing the URL to the image of the recipe.
     /// - `url`: A string slice containing the URL to the recipe's page.
     ///
@@ -225,20 +494,32 @@ impl Recipe {
     /// # Example
     ///
     /// ```rust
-    /// let recipe = Recipe::new("https://example.com/image.jpg", "https://example.com/recipe-page")
+    /// let fetcher = HttpFetcher::new(RateLimit::default(), RetryPolicy::default()).expect("Failed to build client");
+    /// let recipe = Recipe::new(&fetcher, "https://example.com/image.jpg", "https://example.com/recipe-page")
     ///     .expect("Failed to create recipe");
     /// ```
     ///
     /// # Errors
     ///
     /// If parsing the recipe fails (e.g., missing data, invalid format), this function returns an error.
-    pub async fn new(img: &str, url: &str) -> Result<Self, Box<dyn Error>> {
+    pub async fn new(fetcher: &dyn Fetcher, img: &str, url: &str) -> Result<Self, Box<dyn Error>> {
+        Self::with_registry(fetcher, img, url, &Registry::default()).await
+    }
+
+    /// Like `new`, but parses the page with the scrapers in `registry` instead of the
+    /// built-in ones.
+    pub async fn with_registry(
+        fetcher: &dyn Fetcher,
+        img: &str,
+        url: &str,
+        registry: &Registry,
+    ) -> Result<Self, Box<dyn Error>> {
         let mut r = Recipe {
             img: img.into(),
             url: url.into(),
             ..Default::default()
         };
-        match r.parse_recipe().await {
+        match r.parse_recipe(fetcher, registry).await {
             Ok(()) => return Ok(r),
             Err(e) => return Err(e),
         }
@@ -246,243 +527,71 @@ impl Recipe {
 
     /// Parses the recipe out of the recipe's home page
     ///
-    /// Constructing a recipe instance queries the url and extracts the
-    /// relevant data into the recipe strcut. Parsing this information is a
-    /// lot of work, which this function handles
-    async fn parse_recipe(&mut self) -> Result<(), Box<dyn Error>> {
+    /// Fetches the page and hands it to the first scraper in `registry` that handles
+    /// the URL or recognises the page's markup. Pages no scraper handles go to the
+    /// heuristic extractor. The derived fields are filled in afterwards either way.
+    async fn parse_recipe(
+        &mut self,
+        fetcher: &dyn Fetcher,
+        registry: &Registry,
+    ) -> Result<(), Box<dyn Error>> {
         // The document represents the page as whole, starts enabling `find` capabilities
-        let document = get_document(&self.url).await?;
-
-        let mut id = document
-            .find(Class("tasty-recipes-jump-link"))
-            .next()
-            .and_then(|id| id.attr("href").map(|href| href.to_string()))
-            .ok_or("ID not found in document")?; // Converts the Option to Result and propagates error using ?
-
-        // Process the id (assuming you want to remove the '#' and '-jump-target' from the href)
-        id = id
-            .trim_start_matches('#')
-            .trim_end_matches("-jump-target")
-            .to_string();
-
-        if let Some(recipe) = document.find(Attr("id", id.as_str())).next() {
-            let header = recipe.find(Name("header")).next().unwrap();
-
-            self.parse_header(&header)?;
-
-            let body = recipe
-                .find(Class("tasty-recipes-entry-content"))
-                .next()
-                .unwrap();
-
-            self.description = body
-                .find(Class("tasty-recipes-description-body"))
-                .next()
-                .and_then(|d| Some(d.text().trim().into()));
+        let document = get_document(fetcher, &self.url).await?;
+        self.trace.url = self.url.clone();
 
-            if let Some(ul_containter) = body.find(Class("tasty-recipes-ingredients")).next() {
-                for ul in ul_containter.find(Name("ul")) {
-                    self.parse_ingredients(&ul)?;
-                }
-            }
-
-            if let Some(instructions_block) = body.find(Class("tasty-recipes-instructions")).next()
-            {
-                self.parse_instructions(&instructions_block)?;
+        match registry.find(&self.url, &document) {
+            Some(scraper) => {
+                self.trace.record("scraper", scraper.name(), None, None);
+                scraper.parse(self, &document, fetcher).await?;
             }
+            // No known site or plugin markup on the page
+            None => self.parse_fallback(&document)?,
+        }
 
-            if let Some(frame_url) = body
-                .find(Name("iframe"))
-                .next()
-                .and_then(|fr| fr.attr("src").map(String::from))
-            {
-                self.video = Some(frame_url);
-            }
+        self.derive_fields();
 
-            self.notes = match body
-                .find(Class("tasty-recipes-notes"))
-                .next()
-                .and_then(|n| Some(n.text().trim().to_string()))
-            {
-                Some(n) => Some(Self::clean_notes(&n)),
-                None => None,
-            };
+        Ok(())
+    }
 
-            if let Some(details) = body
-                .find(Class("tasty-recipes-other-details"))
-                .next()
-                .and_then(|d| d.find(Name("ul")).next())
-            {
-                for li in details.find(Name("li")) {
-                    match li.attr("class") {
-                        Some(class) => match class {
-                            "prep-time" => {
-                                let prep_time_str = li
-                                    .find(Class("tasty-recipes-prep-time"))
-                                    .next()
-                                    .unwrap()
-                                    .text();
-                                self.prep_time = match u32::from_time_str(&prep_time_str) {
-                                    Ok(t) => t,
-                                    Err(e) => panic!("{}: {}", self.url, e),
-                                };
-                            }
-                            "cook-time" => {
-                                let cook_time_str = li
-                                    .find(Class("tasty-recipes-cook-time"))
-                                    .next()
-                                    .unwrap()
-                                    .text();
-                                self.cook_time = match u32::from_time_str(&cook_time_str) {
-                                    Ok(t) => t,
-                                    Err(e) => panic!("{}: {}", self.url, e),
-                                };
-                            }
-                            "cuisine" => {
-                                self.cuisine = li
-                                    .find(Class("tasty-recipes-cuisine"))
-                                    .next()
-                                    .unwrap()
-                                    .text();
-                            }
-                            "category" => {
-                                self.category = li
-                                    .find(Class("tasty-recipes-category"))
-                                    .next()
-                                    .unwrap()
-                                    .text();
-                            }
-                            "method" => {
-                                self.method = li
-                                    .find(Class("tasty-recipes-method"))
-                                    .next()
-                                    .unwrap()
-                                    .text();
-                            }
-
-                            _ => {}
-                        },
-                        None => {}
-                    }
-                }
-            }
+    /// Parses a page without recognised recipe markup: from its JSON-LD or microdata
+    /// if it has a `Recipe`, otherwise with the heuristic extractor, reporting pages
+    /// that turned into galleries as `E_NOT_A_RECIPE`.
+    fn parse_fallback(&mut self, document: &Document) -> Result<(), Box<dyn Error>> {
+        if self.parse_json_ld(document).is_ok() || self.parse_microdata(document).is_ok() {
+            return Ok(());
+        }
 
-            if let Some(nutrition_url) = body
-                .find(Name("iframe").and(Attr("title", "nutritional information")))
-                .next()
-                .and_then(|nut| nut.attr("data-l-src"))
-            {
-                self.get_macros(format!("https:{}", nutrition_url).as_str())
-                    .await?;
-            } else {
-                self.macros = None
+        if let Err(e) = self.parse_heuristic(document) {
+            if heuristic::is_gallery(document) {
+                Err(ScrapeError::new(
+                    ErrorCode::NotARecipe,
+                    format!("Page is a gallery, not a recipe: {}", self.url),
+                ))?
             }
-
-            Ok(())
-        } else {
-            Err("ID not found in document (after jump link was found)")?
+            return Err(e);
         }
-    }
-
-    /// Parses the recipe's name and total time from the header node.
-    ///
-    /// Extracts the name from an `<h2>` tag and total time from a specific class,
-    /// updating the `Recipe` struct's fields. Panics if the total time is invalid.
-    ///
-    /// # Arguments
-    ///
-    /// - `header`: The HTML node containing the recipe's header information.
-    ///
-    /// # Returns
-    ///
-    /// - `Ok(())` if parsing is successful, or an error if parsing fails.    
-    fn parse_header(&mut self, header: &Node) -> Result<(), Box<dyn Error>> {
-        self.name = header.find(Name("h2")).next().unwrap().text();
-        let time_str = header
-            .find(Class("tasty-recipes-total-time"))
-            .next()
-            .unwrap()
-            .text();
-
-        self.total_time = match u32::from_time_str(&time_str) {
-            Ok(t) => t,
-            Err(e) => panic!("{}: {}", self.url, e),
-        };
 
         Ok(())
     }
 
-    /// Parses the ingredient list from a given HTML node, extracting ingredient names, quantities, units, and preparation details.
-    /// The ingredients are then added to the recipe's list of ingredients.
-    ///
-    /// # Arguments
-    /// - `list`: The HTML node containing the ingredient list (`<ul>` or `<li>` elements).
-    ///
-    /// # Returns
-    /// A `Result` that indicates whether the parsing succeeded (`Ok(())`) or failed (`Err`).    
-    fn parse_ingredients(&mut self, list: &Node) -> Result<(), Box<dyn Error>> {
-        let mut ingredients = Vec::new();
-
-        for ingredient in list.find(Name("li")) {
-            let name = match ingredient.find(Name("strong")).next() {
-                Some(n) => n.text(),
-                None => match ingredient.find(Name("b")).next() {
-                    Some(n) => n.text(),
-                    None => Err(format!(
-                        "Error building ingredients for: {}. No ingredient name found:{} ",
-                        self.url,
-                        ingredient.text()
-                    ))?,
-                },
-            };
-
-            if let Some(span) = ingredient.find(Name("span")).nth(1) {
-                let quantity = match span.attr("data-amount") {
-                    Some(q) => q.parse::<f32>().unwrap(),
-                    None => span
-                        .find(Name("span"))
-                        .next()
-                        .ok_or("Could not parse inner span")?
-                        .attr("data-amount")
-                        .ok_or("Could not parse inner span")?
-                        .parse::<f32>()?,
-                };
-
-                let units = span.attr("data-unit").and_then(|u| match Unit::from(u) {
-                    Ok(u) => Some(u),
-                    Err(_) => None, // just return none if the parsing fails
-                });
-
-                let prepped = ingredient
-                    .find(Name("em"))
-                    .next()
-                    .and_then(|p| Some(p.text()));
-
-                ingredients.push(Ingredient {
-                    name,
-                    quantity,
-                    units,
-                    prepped,
-                });
-            } else {
-                // no units (things like parsley, optional for seriving: https://www.aheadofthyme.com/minestrone-soup/)
-                let prepped = ingredient
-                    .find(Name("em"))
-                    .next()
-                    .and_then(|p| Some(p.text()));
-
-                ingredients.push(Ingredient {
-                    name,
-                    quantity: 0.0,
-                    units: None,
-                    prepped,
-                });
-            }
+    /// Fills in the fields computed from already parsed ones: spice level, cooking
+    /// methods, storage info, appliance variants, make-ahead flags, the fallback image
+    /// alt text and the overall parse confidence.
+    fn derive_fields(&mut self) {
+        self.spice_level = SpiceLevel::estimate(&self.ingredients);
+        self.methods = methods::classify(&self.instructions);
+        self.storage = self.notes.as_deref().and_then(StorageInfo::from_notes);
+        self.variants = variants::extract(&mut self.instructions, self.notes.as_deref());
+        for instruction in &mut self.instructions {
+            instruction.assign_anchors();
         }
-
-        self.add_ingredients(ingredients);
-
-        Ok(())
+        self.prep_ahead = MakeAhead::detect(
+            self.notes.as_deref(),
+            &self.instructions,
+            self.storage.as_ref(),
+        );
+        self.img_alt = self.generated_alt();
+        self.parse_confidence = self.trace.confidence();
     }
 
     /// Adds ingredients to the recipe, either updating the quantity of existing ingredients or adding new ones.
@@ -509,64 +618,26 @@ impl Recipe {
         }
     }
 
-    /// Parses the instructions from the provided HTML node and stores them in the recipe.
-    /// It handles both single and multiple ordered lists for steps and organizes them under section headers.
-    ///
-    /// # Arguments
-    /// - `list`: The HTML node containing the instructions to be parsed.
-    ///
-    /// # Returns
-    /// - `Result<(), Box<dyn Error>>`: Returns `Ok(())` on success, or an error if parsing fails.
-    fn parse_instructions(&mut self, list: &Node) -> Result<(), Box<dyn Error>> {
-        // "https://www.aheadofthyme.com/easy-meat-lasagna/" for some reason not grabbing all instructions, but other similar examples are
-        let h4_blocks: Vec<_> = list.find(Name("h4")).collect();
-
-        let mut ol_blocks: Vec<_> = list
-            .find(Name("div"))
-            .nth(1)
-            .expect("Could not find nth child in parse instructions")
-            .children()
-            .filter(|child| child.name() == Some("ol"))
-            .collect();
-
-        if ol_blocks.len() == 1 {
-            // we have 1 ol block
-            let ol_block = ol_blocks
-                .pop()
-                .expect("Ol blocks failed to pop the element");
-
-            self.instructions = vec![Instruction {
-                section: None,
-                steps: ol_block.find(Name("li")).map(|step| step.text()).collect(),
-            }];
-        } else {
-            let mut instructions = Vec::new();
-
-            for (h4, ol) in h4_blocks.iter().zip(ol_blocks.iter()) {
-                let instruction = Instruction {
-                    section: Some(String::from(h4.text().trim_end_matches(":"))),
-                    steps: ol.find(Name("li")).map(|step| step.text()).collect(),
-                };
-                instructions.push(instruction);
-            }
-
-            self.instructions = instructions;
-        }
-
-        Ok(())
-    }
-
     /// Fetches and parses nutritional information from a given URL.
     /// Extracts the recipe's macros and servings from a JavaScript variable and normalizes the macros by servings.
     ///
     /// # Arguments
+    /// - `fetcher`: Fetches the label page.
     /// - `url`: The URL where the nutritional data can be found.
     ///
     /// # Returns
     /// - `Result<(), Box<dyn Error>>`: Returns `Ok(())` on success, or an error if parsing fails.
-    async fn get_macros(&mut self, url: &str) -> Result<(), Box<dyn Error>> {
-        let document = get_document(url).await?;
+    async fn get_macros(&mut self, fetcher: &dyn Fetcher, url: &str) -> Result<(), Box<dyn Error>> {
+        let document = get_document(fetcher, url).await?;
+        self.parse_nutrifox(&document, url)
+    }
 
+    /// Extracts the macros and servings from a fetched Nutrifox label page.
+    ///
+    /// # Arguments
+    /// - `document`: The label page.
+    /// - `url`: The label's URL, for error messages.
+    fn parse_nutrifox(&mut self, document: &Document, url: &str) -> Result<(), Box<dyn Error>> {
         if let Some(data) = document.find(Name("script")).next() {
             let re = Regex::new(r"var preloaded = \{'recipe': (.*)\}")?;
 
@@ -577,10 +648,17 @@ impl Recipe {
                 if let (Some(macros), Some(servings)) =
                     (json_value.get("nutrients"), json_value.get("servings"))
                 {
-                    self.servings = servings.as_u64().expect("Failed to parse servings to u64");
+                    self.servings = servings.as_u64().ok_or_else(|| {
+                        ScrapeError::new(
+                            ErrorCode::Parse,
+                            format!("Servings is not a whole number: {}", servings),
+                        )
+                    })?;
                     self.macros = serde_json::from_value(macros.clone())?;
                     if let Some(macros) = self.macros.as_mut() {
                         macros.normalize_by_servings(self.servings);
+                        macros.source = NutritionSource::Nutrifox;
+                        macros.confidence = macros.source.confidence();
                     } else {
                         self.macros = None
                     }
@@ -588,31 +666,71 @@ impl Recipe {
                     self.macros = None;
                 }
             } else {
-                Err(format!("Regex pattern failed from: {}", data.text()))?
+                Err(ScrapeError::new(
+                    ErrorCode::NutritionRegex,
+                    format!("Regex pattern failed from: {}", data.text()),
+                ))?
             }
         } else {
-            Err(format!("Could not find script tag from: {}", url))?
+            Err(ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!("Could not find script tag from: {}", url),
+            ))?
         }
 
         Ok(())
     }
 
+    /// Sets the image alt text, keeping the generated description if the source alt is
+    /// missing or blank.
+    fn set_img_alt(&mut self, alt: Option<String>) {
+        if let Some(alt) = alt.map(|a| a.trim().to_string()).filter(|a| !a.is_empty()) {
+            self.img_alt = alt;
+        }
+    }
+
+    /// Builds a basic image description from the recipe name and category.
+    fn generated_alt(&self) -> String {
+        if self.category.trim().is_empty() {
+            format!("Photo of {}", self.name.trim())
+        } else {
+            format!("Photo of {} ({})", self.name.trim(), self.category.trim())
+        }
+    }
+
+    /// Reads the text of a notes block, one paragraph (`<p>` or `<li>`) per line, so the
+    /// paragraph breaks survive even when the page's markup has no newlines between tags.
+    fn notes_text(node: &Node) -> String {
+        let paragraphs: Vec<String> = node
+            .find(Name("p").or(Name("li")))
+            .map(|p| p.text())
+            .collect();
+
+        if paragraphs.is_empty() {
+            node.text()
+        } else {
+            paragraphs.join("\n")
+        }
+    }
+
     /// Cleans up the input string by normalizing spaces, newlines, and tabs.
-    /// Replaces non-breaking spaces with regular spaces, consolidates multiple newlines or tabs,
-    /// and trims leading/trailing spaces.
+    /// Replaces non-breaking spaces with regular spaces, collapses runs of spaces and tabs,
+    /// keeps a single newline between paragraphs, and trims leading/trailing spaces.
     ///
     /// # Arguments
     /// - `input`: The string to be cleaned.
     ///
     /// # Returns
-    /// - A cleaned-up version of the input string.    
+    /// - A cleaned-up version of the input string.
     fn clean_notes(input: &str) -> String {
         // Remove all non-breaking spaces (\u{a0}) and replace with regular spaces
         let input = input.replace("\u{a0}", " ");
 
-        // Normalize newlines and tabs: replace multiple newlines or tabs with a single newline or space
-        let re = Regex::new(r"[\n\t]+").unwrap();
-        let input = re.replace_all(&input, " ").to_string();
+        // Collapse spaces and tabs, and blank lines between paragraphs into a single newline
+        let spaces = Regex::new(r"[ \t]+").unwrap();
+        let input = spaces.replace_all(&input, " ");
+        let breaks = Regex::new(r" ?\n[\s]*").unwrap();
+        let input = breaks.replace_all(&input, "\n");
 
         // Trim leading and trailing spaces
         input.trim().to_string()
@@ -625,7 +743,8 @@ impl Recipe {
 /// `div` element with the class `"entry-content"`. Each recipe entry is identified by a
 /// `figure` tag, which contains a link (`a`) to the recipe's URL and an image (`img`)
 /// representing the recipe's image source. Both the URL and image source must be present
-/// for a valid recipe to be added to the result list.
+/// for a valid recipe to be added to the result list. The image's `alt` text is kept when
+/// present.
 ///
 /// The function creates a new `Recipe` instance for each valid entry and collects them
 /// into a `Vec<Recipe>`. If the `Recipe::new` constructor fails, an error message is
@@ -633,6 +752,7 @@ impl Recipe {
 ///
 /// # Arguments
 ///
+/// * `fetcher` - Fetches each recipe page found on the listing.
 /// * `document` - A reference to the `Document` to scrape the recipe information from.
 ///
 /// # Returns
@@ -644,7 +764,7 @@ impl Recipe {
 ///
 /// ```rust
 /// let document = scraper::Html::parse_document("<html>...</html>");
-/// let recipes = get_recipes(&document);
+/// let recipes = get_recipes(&fetcher, &document);
 /// for recipe in recipes {
 ///     println!("{}", recipe);
 /// }
@@ -659,7 +779,17 @@ impl Recipe {
 /// # Panics
 ///
 /// This function will not panic under normal circumstances.
-pub async fn get_recipes(document: &Document) -> Vec<Recipe> {
+pub async fn get_recipes(fetcher: &dyn Fetcher, document: &Document) -> Vec<Recipe> {
+    get_recipes_reporting(fetcher, document, &mut Vec::new()).await
+}
+
+/// Like `get_recipes`, but also pushes a `Failure` onto `failures` for every recipe that
+/// could not be created, for run summaries.
+pub async fn get_recipes_reporting(
+    fetcher: &dyn Fetcher,
+    document: &Document,
+    failures: &mut Vec<Failure>,
+) -> Vec<Recipe> {
     let mut out: Vec<Recipe> = Vec::new();
 
     if let Some(entry_content) = document
@@ -672,19 +802,20 @@ pub async fn get_recipes(document: &Document) -> Vec<Recipe> {
                 .next()
                 .and_then(|a| a.attr("href").map(|href| href.to_string()));
 
-            let img = figure
-                .find(Name("img"))
-                .next()
-                .and_then(|img| img.attr("data-lazy-src").map(|src| src.to_string()));
+            let img_node = figure.find(Name("img")).next();
+            let img = img_node.and_then(|img| img.attr("data-lazy-src").map(|src| src.to_string()));
+            let alt = img_node.and_then(|img| img.attr("alt").map(|alt| alt.to_string()));
 
             // Only push if both `url` and `img` are available
             if let (Some(url), Some(img)) = (url, img) {
-                match Recipe::new(&img, &url).await {
-                    Ok(r) => {
+                match Recipe::new(fetcher, &img, &url).await {
+                    Ok(mut r) => {
+                        r.set_img_alt(alt);
                         out.push(r);
                     }
                     Err(e) => {
-                        println!("Url: {} Threw the following: {}", url, e)
+                        println!("Url: {} Threw the following: {}", url, error::describe(&*e));
+                        failures.push(Failure::new(&url, &*e));
                     }
                 }
             }
@@ -694,10 +825,133 @@ pub async fn get_recipes(document: &Document) -> Vec<Recipe> {
     out
 }
 
+/// Fetches a listing page and the pages after it, following "next" and numbered
+/// pagination links (see `discovery::next_page`). At most `MAX_LISTING_PAGES` pages are
+/// read; a later page that fails to load ends the listing and is recorded in
+/// `page_failures`.
+///
+/// # Errors
+///
+/// Returns an error if `listing` is not a valid URL or its first page cannot be fetched.
+async fn listing_pages(
+    fetcher: &dyn Fetcher,
+    listing: &str,
+    page_failures: &mut Vec<Failure>,
+) -> Result<Vec<(Url, Document)>, Box<dyn Error>> {
+    let mut pages = vec![(Url::parse(listing)?, get_document(fetcher, listing).await?)];
+
+    while let Some((url, document)) = pages.last() {
+        let Some(next) = discovery::next_page(document, url) else {
+            break;
+        };
+        if pages.iter().any(|(seen, _)| *seen == next) || pages.len() >= MAX_LISTING_PAGES {
+            break;
+        }
+
+        match get_document(fetcher, next.as_str()).await {
+            Ok(doc) => pages.push((next, doc)),
+            Err(e) => {
+                println!(
+                    "Url: {} Threw the following: {}",
+                    next,
+                    error::describe(&*e)
+                );
+                page_failures.push(Failure::new(next.as_str(), &*e));
+                break;
+            }
+        }
+    }
+
+    Ok(pages)
+}
+
+/// Scrapes every recipe on a listing page and on the pages after it, so that roundups
+/// split over several pages are read in full. Recipes that fail to parse are recorded
+/// in `failures`, and later listing pages that fail to load in `page_failures`.
+///
+/// # Errors
+///
+/// Returns an error if `listing` is not a valid URL or its first page cannot be fetched.
+pub async fn get_listing_recipes(
+    fetcher: &dyn Fetcher,
+    listing: &str,
+    page_failures: &mut Vec<Failure>,
+    failures: &mut Vec<Failure>,
+) -> Result<Vec<Recipe>, Box<dyn Error>> {
+    let mut out = Vec::new();
+    for (_, document) in listing_pages(fetcher, listing, page_failures).await? {
+        out.extend(get_recipes_reporting(fetcher, &document, failures).await);
+    }
+
+    Ok(out)
+}
+
+/// Scrapes every post listed in a category or tag archive (such as `/category/dinner/`)
+/// across all of its pages, recording the archive's term in each recipe's
+/// `discovered_under`. Posts that fail to parse are recorded in `failures`, and later
+/// archive pages that fail to load in `page_failures`.
+///
+/// # Errors
+///
+/// Returns an error if `archive` is not a valid URL or its first page cannot be fetched.
+pub async fn get_archive_recipes(
+    fetcher: &dyn Fetcher,
+    archive: &str,
+    page_failures: &mut Vec<Failure>,
+    failures: &mut Vec<Failure>,
+) -> Result<Vec<Recipe>, Box<dyn Error>> {
+    let mut urls: Vec<String> = Vec::new();
+    let mut term = None;
+    for (url, document) in listing_pages(fetcher, archive, page_failures).await? {
+        term = term.or_else(|| discovery::archive_term(&url));
+        for link in discovery::archive_links(&document, &url) {
+            if !urls.contains(&link.to_string()) {
+                urls.push(link.to_string());
+            }
+        }
+    }
+
+    let mut out = get_recipes_from_urls(fetcher, &urls, failures).await;
+    if let Some(term) = term {
+        for recipe in &mut out {
+            if !recipe.discovered_under.contains(&term) {
+                recipe.discovered_under.push(term.clone());
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Scrapes each of `urls` as a recipe page, such as the URLs found by
+/// `discovery::sitemap_urls`. Pages that fail to parse are recorded in `failures`.
+///
+/// The recipe image comes from the page itself (JSON-LD or microdata) where it has one,
+/// since there is no listing thumbnail to take it from.
+pub async fn get_recipes_from_urls(
+    fetcher: &dyn Fetcher,
+    urls: &[String],
+    failures: &mut Vec<Failure>,
+) -> Vec<Recipe> {
+    let mut out: Vec<Recipe> = Vec::new();
+
+    for url in urls {
+        match Recipe::new(fetcher, "", url).await {
+            Ok(r) => out.push(r),
+            Err(e) => {
+                println!("Url: {} Threw the following: {}", url, error::describe(&*e));
+                failures.push(Failure::new(url, &*e));
+            }
+        }
+    }
+
+    out
+}
+
 /// Retrieves an HTML document from a specified URL.
 ///
-/// This function performs a blocking HTTP GET request to the provided `url` and attempts
-/// to fetch the content of the page. If the request is successful, the function converts
+/// This function fetches the page at the provided `url` with `fetcher` and attempts
+/// to read its content. If the request is successful, the function converts
 /// the response text (HTML) into a `Document` object that can be further processed for scraping.
 ///
 /// If the request or the text conversion fails, an error message is returned as a `Result::Err`
@@ -706,6 +960,7 @@ pub async fn get_recipes(document: &Document) -> Vec<Recipe> {
 ///
 /// # Arguments
 ///
+/// * `fetcher` - The `Fetcher` the request goes through, shared by the whole run.
 /// * `url` - The URL from which the HTML document is to be fetched. This is a string slice (`&str`).
 ///
 /// # Returns
@@ -717,7 +972,7 @@ pub async fn get_recipes(document: &Document) -> Vec<Recipe> {
 ///
 /// ```rust
 /// let url = "https://example.com";
-/// match get_document(url) {
+/// match get_document(&fetcher, url) {
 ///     Ok(doc) => println!("Document retrieved successfully"),
 ///     Err(e) => println!("Error retrieving document: {}", e),
 /// }
@@ -733,31 +988,75 @@ pub async fn get_recipes(document: &Document) -> Vec<Recipe> {
 /// # Panics
 ///
 /// This function will not panic under normal circumstances, as it uses error handling to report issues.
-pub async fn get_document(url: &str) -> Result<Document, Box<dyn Error>> {
-    let response = fetch_data(url).await?;
+pub async fn get_document(fetcher: &dyn Fetcher, url: &str) -> Result<Document, Box<dyn Error>> {
+    let response = fetcher.fetch(url).await?;
 
     // Convert the HTML string into a Document
     Ok(Document::from(response.as_str()))
 }
 
-pub async fn get_recipe_test(_id: u8) -> Recipe {
+pub async fn get_recipe_test(fetcher: &dyn Fetcher, _id: u8) -> Recipe {
     let img = "";
 
     let url = "https://www.aheadofthyme.com/easy-meat-lasagna/";
 
-    Recipe::new(img, url).await.expect("Failed to get recipe")
+    Recipe::new(fetcher, img, url)
+        .await
+        .expect("Failed to get recipe")
+}
+
+/// Entry points for the fuzz targets in `fuzz/`. Not part of the public API.
+#[doc(hidden)]
+pub mod fuzzing {
+    use super::*;
+
+    /// Parses every `<ul>` in `html` as a Tasty ingredient list.
+    pub fn ingredients(html: &str) {
+        let document = Document::from(html);
+        for list in document.find(Name("ul")) {
+            let _ = Recipe::default().parse_ingredients(&list);
+        }
+    }
+
+    /// Runs the heuristic extractor and the derived fields over `html`.
+    pub fn heuristic(html: &str) {
+        let mut recipe = Recipe::default();
+        if recipe.parse_heuristic(&Document::from(html)).is_ok() {
+            recipe.derive_fields();
+        }
+    }
+
+    /// Parses `html` as a Nutrifox label page.
+    pub fn nutrifox(html: &str) {
+        let _ = Recipe::default().parse_nutrifox(&Document::from(html), "fuzz");
+    }
+
+    /// Parses `text` as a plain-text nutrition paragraph.
+    pub fn nutrition_text(text: &str) {
+        let _ = Macros::from_text(text);
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use fetch::tests::FakeFetcher;
+    use fetch::HttpFetcher;
+    use retry::RetryPolicy;
+    use throttle::RateLimit;
+
     #[tokio::test]
     #[ignore]
     async fn test_get_recipes() -> Result<(), Box<dyn std::error::Error>> {
-        let document = get_document("https://www.aheadofthyme.com/30-best-shrimp-recipes/").await?;
+        let fetcher = HttpFetcher::new(RateLimit::default(), RetryPolicy::default())?;
+        let document = get_document(
+            &fetcher,
+            "https://www.aheadofthyme.com/30-best-shrimp-recipes/",
+        )
+        .await?;
 
         // Assuming `get_recipe_urls` is a function that takes a `Document` and returns URLs
-        let urls = get_recipes(&document).await;
+        let urls = get_recipes(&fetcher, &document).await;
 
         println!("{:#?}", &urls[..5]);
         // Continue with your logic, parsing `response`, etc.
@@ -770,7 +1069,11 @@ mod tests {
 
         let url = "https://www.aheadofthyme.com/easy-meat-lasagna/";
 
-        let r = Recipe::new(img, url).await.expect("Failed to get recipe");
+        let fetcher = HttpFetcher::new(RateLimit::default(), RetryPolicy::default())
+            .expect("Failed to build client");
+        let r = Recipe::new(&fetcher, img, url)
+            .await
+            .expect("Failed to get recipe");
 
         println!("{:#?}", r);
     }
@@ -782,8 +1085,157 @@ mod tests {
         let mut r = Recipe {
             ..Default::default()
         };
-        r.get_macros(&url).await.expect("Failed to get macros");
+        let fetcher = HttpFetcher::new(RateLimit::default(), RetryPolicy::default())
+            .expect("Failed to build client");
+        r.get_macros(&fetcher, url)
+            .await
+            .expect("Failed to get macros");
 
         println!("{:#?}", r);
     }
+
+    #[tokio::test]
+    async fn test_get_listing_recipes() {
+        let recipe_page = |name: &str| {
+            format!(
+                r#"<script type="application/ld+json">{{"@type": "Recipe", "name": "{}",
+                    "recipeIngredient": ["1 onion"],
+                    "recipeInstructions": [{{"@type": "HowToStep", "text": "Cook it."}}]}}</script>"#,
+                name
+            )
+        };
+        let listing = |links: &[&str], next: Option<&str>| {
+            let figures: String = links
+                .iter()
+                .map(|url| {
+                    format!(
+                        r#"<figure><a href="{}"><img data-lazy-src="{}.jpg" alt="Photo"></a></figure>"#,
+                        url, url
+                    )
+                })
+                .collect();
+            let next = next
+                .map(|n| format!(r#"<a class="next page-numbers" href="{}">Next</a>"#, n))
+                .unwrap_or_default();
+            format!(
+                r#"<div class="entry-content">{}</div><nav class="pagination">{}</nav>"#,
+                figures, next
+            )
+        };
+
+        let page_one = listing(
+            &["https://example.com/soup/", "https://example.com/broken/"],
+            Some("https://example.com/roundup/page/2/"),
+        );
+        // Page three is missing, which ends the listing
+        let page_two = listing(
+            &["https://example.com/stew/"],
+            Some("https://example.com/roundup/page/3/"),
+        );
+        let (soup, stew) = (recipe_page("Soup"), recipe_page("Stew"));
+        let fetcher = FakeFetcher::new(&[
+            ("https://example.com/roundup/", &page_one),
+            ("https://example.com/roundup/page/2/", &page_two),
+            ("https://example.com/soup/", &soup),
+            ("https://example.com/stew/", &stew),
+        ]);
+
+        let (mut page_failures, mut failures) = (Vec::new(), Vec::new());
+        let recipes = get_listing_recipes(
+            &fetcher,
+            "https://example.com/roundup/",
+            &mut page_failures,
+            &mut failures,
+        )
+        .await
+        .expect("Failed to read listing");
+
+        assert_eq!(
+            recipes.iter().map(|r| r.name.as_str()).collect::<Vec<_>>(),
+            vec!["Soup", "Stew"]
+        );
+        assert_eq!(recipes[0].img, "https://example.com/soup/.jpg");
+        assert_eq!(failures.len(), 1);
+        assert_eq!(failures[0].url, "https://example.com/broken/");
+        assert_eq!(page_failures.len(), 1);
+        assert_eq!(page_failures[0].url, "https://example.com/roundup/page/3/");
+    }
+
+    #[test]
+    fn test_ingredient_flags() {
+        assert_eq!(
+            Ingredient::flags("Salt and pepper, to taste"),
+            (false, true)
+        );
+        assert_eq!(
+            Ingredient::flags("1/4 cup pine nuts (Optional)"),
+            (true, false)
+        );
+        assert_eq!(Ingredient::flags("2 cups flour"), (false, false));
+    }
+
+    #[test]
+    fn test_json_round_trip() {
+        let document = Document::from(
+            r#"<html><body>
+                <h1>Weeknight Pizza</h1>
+                <h3>Ingredients</h3>
+                <ul>
+                    <li>1 batch <a href="/pizza-dough/">pizza dough</a></li>
+                    <li>½ cup Rao's marinara sauce</li>
+                    <li>Chili flakes (optional)</li>
+                    <li>Salt, to taste</li>
+                </ul>
+                <h3>Instructions</h3>
+                <ol><li>Bake at 475°F for 12 minutes.</li><li>Slice and serve.</li></ol>
+                <p>Calories: 610, Fat: 22g, Protein: 25g</p>
+            </body></html>"#,
+        );
+
+        let mut r = Recipe {
+            url: "https://www.aheadofthyme.com/weeknight-pizza/".into(),
+            notes: Some("Leftovers keep in the fridge for 3 days. Freezes well.".into()),
+            ..Default::default()
+        };
+        r.parse_heuristic(&document)
+            .expect("Failed to parse fixture");
+        r.derive_fields();
+
+        let json = serde_json::to_value(&r).expect("Failed to serialize");
+        let back: Recipe = serde_json::from_value(json.clone()).expect("Failed to deserialize");
+
+        assert_eq!(serde_json::to_value(&back).unwrap(), json);
+        assert_eq!(back.sub_recipes().count(), 1);
+        assert_eq!(back.instructions[0].anchors.len(), 2);
+        assert!(back.macros.is_some() && back.storage.is_some());
+    }
+
+    #[test]
+    fn test_convert_quantity() {
+        assert_eq!(convert_quantity(3.0, "teaspoons", "tablespoon"), Some(1.0));
+        assert_eq!(convert_quantity(0.5, "cup", "tablespoons"), Some(8.0));
+        assert_eq!(convert_quantity(1.0, "cup", "lb"), None);
+        assert_eq!(convert_quantity(1.0, "bunch", "bunch"), None);
+    }
+
+    #[test]
+    fn test_deserialize_legacy_macros() {
+        let mut json = serde_json::to_value(Macros::default()).unwrap();
+        let macros = json.as_object_mut().unwrap();
+        macros.remove("source");
+        macros.remove("confidence");
+
+        let macros: Macros = serde_json::from_value(json).expect("Failed to deserialize");
+        assert_eq!(macros.source, NutritionSource::Nutrifox);
+        assert_eq!(macros.confidence, Confidence::High);
+    }
+
+    #[test]
+    fn test_deserialize_without_img_alt() {
+        let mut json = serde_json::to_value(Recipe::default()).unwrap();
+        json.as_object_mut().unwrap().remove("img_alt");
+
+        let r: Recipe = serde_json::from_value(json).expect("Failed to deserialize");
+        assert_eq!(r.img_alt, "");
+    }
 }
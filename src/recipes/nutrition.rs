@@ -0,0 +1,192 @@
+use regex::Regex;
+use serde_json::Value;
+
+use super::{Macros, Nutrient, NutritionSource};
+use crate::utils::normalize_text;
+
+/// Fewer matches than this is more likely prose mentioning "protein" than a nutrition line.
+const MIN_NUTRIENTS: usize = 2;
+
+impl Macros {
+    /// Parses a plain-text nutrition paragraph ("Calories: 420, Fat: 12g, Sodium: 600mg")
+    /// into per-serving macros tagged with `NutritionSource::Text`.
+    ///
+    /// # Returns
+    ///
+    /// - `None` if fewer than `MIN_NUTRIENTS` nutrients are recognised.
+    pub(super) fn from_text(text: &str) -> Option<Self> {
+        let re = Regex::new(
+            r"(?i)\b((?:mono|poly)?unsaturated fat|saturated fat|trans fat|fat|calories|carbohydrates?|carbs|fiber|fibre|sugars?|protein|sodium|cholesterol|potassium|calcium|iron)\s*:?\s*(\d{1,3}(?:,\d{3})+(?:\.\d+)?|\d+(?:\.\d+)?)\s*(kcal|mg|g)?\b",
+        )
+        .unwrap();
+
+        let mut macros = Macros::default();
+        let mut found = 0;
+
+        for caps in re.captures_iter(&normalize_text(text)) {
+            let label = caps[1].to_lowercase();
+            // Thousands separators, as in "Sodium: 1,200mg"
+            let Ok(quantity) = caps[2].replace(',', "").parse::<f64>() else {
+                continue;
+            };
+
+            let (nutrient, label, default_unit) = match label.as_str() {
+                "calories" => (&mut macros.ENERC_KCAL, "Calories", "kcal"),
+                "fat" => (&mut macros.FAT, "Fat", "g"),
+                "saturated fat" => (&mut macros.FASAT, "Saturated Fat", "g"),
+                "trans fat" => (&mut macros.FATRN, "Trans Fat", "g"),
+                "carbohydrate" | "carbohydrates" | "carbs" => {
+                    (&mut macros.CHOCDF, "Carbohydrates", "g")
+                }
+                "fiber" | "fibre" => (&mut macros.FIBTG, "Fiber", "g"),
+                "sugar" | "sugars" => (&mut macros.SUGAR, "Sugar", "g"),
+                "protein" => (&mut macros.PROCNT, "Protein", "g"),
+                "sodium" => (&mut macros.NA, "Sodium", "mg"),
+                "cholesterol" => (&mut macros.CHOLE, "Cholesterol", "mg"),
+                "potassium" => (&mut macros.K, "Potassium", "mg"),
+                "calcium" => (&mut macros.CA, "Calcium", "mg"),
+                "iron" => (&mut macros.FE, "Iron", "mg"),
+                // Matched only so their "fat" isn't read as total fat
+                _ => continue,
+            };
+
+            *nutrient = Nutrient {
+                unit: caps
+                    .get(3)
+                    .map_or(default_unit, |u| u.as_str())
+                    .to_lowercase(),
+                label: label.into(),
+                quantity,
+                daily: 0.0,
+            };
+            found += 1;
+        }
+
+        if found < MIN_NUTRIENTS {
+            return None;
+        }
+
+        macros.source = NutritionSource::Text;
+        macros.confidence = macros.source.confidence();
+        Some(macros)
+    }
+}
+
+impl Macros {
+    /// Reads a schema.org `NutritionInformation` object (`{"calories": "420 kcal",
+    /// "fatContent": "12 g", ...}`) into per-serving macros tagged with
+    /// `NutritionSource::JsonLd`.
+    ///
+    /// # Returns
+    ///
+    /// - `None` if fewer than `MIN_NUTRIENTS` nutrients are present.
+    pub(super) fn from_json_ld(nutrition: &Value) -> Option<Self> {
+        let re = Regex::new(r"(\d+(?:\.\d+)?)\s*([a-zA-Z]+)?").unwrap();
+
+        let mut macros = Macros::default();
+        let mut found = 0;
+
+        for (key, value) in nutrition.as_object()? {
+            let (nutrient, label, default_unit) = match key.as_str() {
+                "calories" => (&mut macros.ENERC_KCAL, "Calories", "kcal"),
+                "fatContent" => (&mut macros.FAT, "Fat", "g"),
+                "saturatedFatContent" => (&mut macros.FASAT, "Saturated Fat", "g"),
+                "transFatContent" => (&mut macros.FATRN, "Trans Fat", "g"),
+                "carbohydrateContent" => (&mut macros.CHOCDF, "Carbohydrates", "g"),
+                "fiberContent" => (&mut macros.FIBTG, "Fiber", "g"),
+                "sugarContent" => (&mut macros.SUGAR, "Sugar", "g"),
+                "proteinContent" => (&mut macros.PROCNT, "Protein", "g"),
+                "sodiumContent" => (&mut macros.NA, "Sodium", "mg"),
+                "cholesterolContent" => (&mut macros.CHOLE, "Cholesterol", "mg"),
+                _ => continue,
+            };
+
+            let text = match value {
+                Value::String(s) => s.clone(),
+                Value::Number(n) => n.to_string(),
+                _ => continue,
+            };
+            let Some(caps) = re.captures(&text) else {
+                continue;
+            };
+            let Ok(quantity) = caps[1].parse::<f64>() else {
+                continue;
+            };
+
+            *nutrient = Nutrient {
+                unit: caps
+                    .get(2)
+                    .map_or(default_unit, |u| u.as_str())
+                    .to_lowercase(),
+                label: label.into(),
+                quantity,
+                daily: 0.0,
+            };
+            found += 1;
+        }
+
+        if found < MIN_NUTRIENTS {
+            return None;
+        }
+
+        macros.source = NutritionSource::JsonLd;
+        macros.confidence = macros.source.confidence();
+        Some(macros)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipes::Confidence;
+
+    #[test]
+    fn test_from_text() {
+        let macros = Macros::from_text(
+            "Nutrition: Calories: 420, Total Fat: 12g, Saturated Fat: 3.5g, Sodium: 600mg, Protein: 25 g",
+        )
+        .expect("Expected macros");
+
+        assert_eq!(macros.ENERC_KCAL.quantity, 420.0);
+        assert_eq!(macros.ENERC_KCAL.unit, "kcal");
+        assert_eq!(macros.FAT.quantity, 12.0);
+        assert_eq!(macros.FASAT.quantity, 3.5);
+        assert_eq!(macros.NA.unit, "mg");
+        assert_eq!(macros.PROCNT.quantity, 25.0);
+        assert_eq!(macros.source, NutritionSource::Text);
+        assert_eq!(macros.confidence, Confidence::Medium);
+
+        assert!(Macros::from_text("Packed with protein and flavour.").is_none());
+    }
+
+    #[test]
+    fn test_from_text_unsaturated_and_thousands() {
+        let macros = Macros::from_text(
+            "Fat: 14g, Saturated Fat: 4g, Monounsaturated Fat: 5g, Polyunsaturated Fat: 3g, Sodium: 1,200mg, Calories: 1,050",
+        )
+        .expect("Expected macros");
+
+        assert_eq!(macros.FAT.quantity, 14.0);
+        assert_eq!(macros.FASAT.quantity, 4.0);
+        assert_eq!(macros.NA.quantity, 1200.0);
+        assert_eq!(macros.ENERC_KCAL.quantity, 1050.0);
+    }
+
+    #[test]
+    fn test_from_json_ld() {
+        let macros = Macros::from_json_ld(&serde_json::json!({
+            "@type": "NutritionInformation",
+            "calories": "512 kcal",
+            "fatContent": "21.5 g",
+            "sodiumContent": "740 mg",
+            "servingSize": "1 slice"
+        }))
+        .expect("Expected macros");
+
+        assert_eq!(macros.ENERC_KCAL.quantity, 512.0);
+        assert_eq!(macros.FAT.quantity, 21.5);
+        assert_eq!(macros.NA.unit, "mg");
+        assert_eq!(macros.source, NutritionSource::JsonLd);
+        assert!(Macros::from_json_ld(&serde_json::json!({ "calories": "100" })).is_none());
+    }
+}
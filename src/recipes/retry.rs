@@ -0,0 +1,83 @@
+use reqwest::StatusCode;
+use std::time::Duration;
+
+/// How `HttpFetcher` retries requests that failed for a reason that may pass.
+///
+/// # Fields
+///
+/// - `max_attempts`: The most times a request is sent, counting the first. 1 disables retries.
+/// - `base_delay`: The wait before the first retry; it doubles with each further retry.
+/// - `max_delay`: The longest wait between two attempts.
+/// - `timeout`: How long a single attempt may take before it counts as timed out.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    pub base_delay: Duration,
+    pub max_delay: Duration,
+    pub timeout: Duration,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        RetryPolicy {
+            max_attempts: 4,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(30),
+            timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The wait after the `failures`th failed attempt: `base_delay`, then twice that,
+    /// and so on, up to `max_delay`.
+    pub fn delay(&self, failures: u32) -> Duration {
+        let factor = 2u32.saturating_pow(failures.saturating_sub(1));
+        self.base_delay
+            .checked_mul(factor)
+            .map_or(self.max_delay, |d| d.min(self.max_delay))
+    }
+}
+
+/// Whether a response status may succeed if the request is sent again: server errors
+/// and 408 Request Timeout. Other client errors (404, 410, ...) are permanent. 429 is
+/// handled separately by `throttle`.
+pub fn is_retryable_status(status: StatusCode) -> bool {
+    status.is_server_error() || status == StatusCode::REQUEST_TIMEOUT
+}
+
+/// Whether a failed request may succeed if sent again: timeouts, connection failures,
+/// and retryable statuses.
+pub fn is_retryable(e: &reqwest::Error) -> bool {
+    e.is_timeout() || e.is_connect() || e.status().is_some_and(is_retryable_status)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_delay() {
+        let policy = RetryPolicy {
+            max_attempts: 10,
+            base_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(3),
+            timeout: Duration::from_secs(30),
+        };
+
+        assert_eq!(policy.delay(1), Duration::from_millis(500));
+        assert_eq!(policy.delay(2), Duration::from_secs(1));
+        assert_eq!(policy.delay(3), Duration::from_secs(2));
+        assert_eq!(policy.delay(4), Duration::from_secs(3));
+        assert_eq!(policy.delay(40), Duration::from_secs(3));
+    }
+
+    #[test]
+    fn test_is_retryable_status() {
+        assert!(is_retryable_status(StatusCode::INTERNAL_SERVER_ERROR));
+        assert!(is_retryable_status(StatusCode::SERVICE_UNAVAILABLE));
+        assert!(is_retryable_status(StatusCode::REQUEST_TIMEOUT));
+        assert!(!is_retryable_status(StatusCode::NOT_FOUND));
+        assert!(!is_retryable_status(StatusCode::TOO_MANY_REQUESTS));
+    }
+}
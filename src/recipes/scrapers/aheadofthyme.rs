@@ -0,0 +1,570 @@
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Class, Name, Predicate};
+use std::error::Error;
+
+use super::{ScrapeFuture, SiteScraper};
+use crate::error::{ErrorCode, ScrapeError};
+use crate::recipes::fetch::Fetcher;
+use crate::recipes::selectors::{first_match, tasty};
+use crate::recipes::{brand, sub_recipe, Ingredient, Instruction, Macros, Recipe, Unit};
+use crate::utils::{normalize_text, U32Ext};
+
+/// Scrapes aheadofthyme.com, and any other site using the Tasty Recipes card.
+pub struct AheadOfThymeScraper;
+
+impl SiteScraper for AheadOfThymeScraper {
+    fn name(&self) -> &'static str {
+        "aheadofthyme"
+    }
+
+    fn matches(&self, url: &str) -> bool {
+        super::host(url).is_some_and(|host| host.ends_with("aheadofthyme.com"))
+    }
+
+    fn detects(&self, document: &Document) -> bool {
+        document
+            .find(Class("tasty-recipes-jump-link"))
+            .next()
+            .is_some()
+    }
+
+    fn parse<'a>(
+        &'a self,
+        recipe: &'a mut Recipe,
+        document: &'a Document,
+        fetcher: &'a dyn Fetcher,
+    ) -> ScrapeFuture<'a> {
+        Box::pin(recipe.parse_tasty_card(document, fetcher))
+    }
+}
+
+impl Recipe {
+    /// Parses the Tasty Recipes card the page's jump link points at, falling back to
+    /// the heuristic extractor if the page has no card.
+    async fn parse_tasty_card(
+        &mut self,
+        document: &Document,
+        fetcher: &dyn Fetcher,
+    ) -> Result<(), Box<dyn Error>> {
+        let Some(mut id) = document
+            .find(Class("tasty-recipes-jump-link"))
+            .next()
+            .and_then(|id| id.attr("href").map(|href| href.to_string()))
+        else {
+            // No Tasty Recipes card on the page, fall back to the heuristic extractor
+            return self.parse_fallback(document);
+        };
+
+        // Process the id (assuming you want to remove the '#' and '-jump-target' from the href)
+        id = id
+            .trim_start_matches('#')
+            .trim_end_matches("-jump-target")
+            .to_string();
+
+        if let Some(recipe) = document.find(Attr("id", id.as_str())).next() {
+            // Older cards have no <header> or entry-content wrapper, search the whole card then
+            let header = recipe.find(Name("header")).next().unwrap_or(recipe);
+
+            self.parse_header(&header)?;
+
+            let body = recipe
+                .find(Class("tasty-recipes-entry-content"))
+                .next()
+                .unwrap_or(recipe);
+
+            if let Some(m) = first_match(&body, tasty::DESCRIPTION) {
+                self.description = Some(m.node.text().trim().into());
+                self.trace
+                    .record_match("description", &m, self.description.as_deref(), None);
+            }
+
+            if let Some(m) = first_match(&body, tasty::INGREDIENTS) {
+                self.trace.record_match("ingredients", &m, None, None);
+                for ul in m.node.find(Name("ul")) {
+                    self.parse_ingredients(&ul)?;
+                }
+            }
+
+            if let Some(m) = first_match(&body, tasty::INSTRUCTIONS) {
+                self.parse_instructions(&m.node)?;
+                self.trace.record_match(
+                    "instructions",
+                    &m,
+                    None,
+                    Some(format!(
+                        "{} sections, {} steps",
+                        self.instructions.len(),
+                        self.instructions
+                            .iter()
+                            .map(|i| i.steps.len())
+                            .sum::<usize>()
+                    )),
+                );
+            }
+
+            if let Some(frame_url) = body
+                .find(Name("iframe"))
+                .next()
+                .and_then(|fr| fr.attr("src").map(String::from))
+            {
+                self.video = Some(frame_url);
+            }
+            self.trace
+                .record("video", "iframe[src]", self.video.as_deref(), None);
+
+            if let Some(m) = first_match(&body, tasty::NOTES) {
+                let raw_notes = Self::notes_text(&m.node).trim().to_string();
+                self.notes = Some(Self::clean_notes(&raw_notes));
+                self.trace.record_match(
+                    "notes",
+                    &m,
+                    Some(&raw_notes),
+                    Some("clean_notes".to_string()),
+                );
+            }
+
+            if let Some(details) =
+                first_match(&body, tasty::DETAILS).and_then(|m| m.node.find(Name("ul")).next())
+            {
+                // A detail whose value span is missing is skipped rather than failing the recipe
+                for li in details.find(Name("li")) {
+                    match li.attr("class") {
+                        Some(class) => match class {
+                            "prep-time" => {
+                                let Some(prep_time_str) = li
+                                    .find(Class("tasty-recipes-prep-time"))
+                                    .next()
+                                    .map(|n| n.text())
+                                else {
+                                    continue;
+                                };
+                                self.prep_time = self.parse_time("prep_time", &prep_time_str)?;
+                                self.trace.record(
+                                    "prep_time",
+                                    ".tasty-recipes-prep-time",
+                                    Some(&prep_time_str),
+                                    Some(format!("from_time_str -> {} min", self.prep_time)),
+                                );
+                            }
+                            "cook-time" => {
+                                let Some(cook_time_str) = li
+                                    .find(Class("tasty-recipes-cook-time"))
+                                    .next()
+                                    .map(|n| n.text())
+                                else {
+                                    continue;
+                                };
+                                self.cook_time = self.parse_time("cook_time", &cook_time_str)?;
+                                self.trace.record(
+                                    "cook_time",
+                                    ".tasty-recipes-cook-time",
+                                    Some(&cook_time_str),
+                                    Some(format!("from_time_str -> {} min", self.cook_time)),
+                                );
+                            }
+                            "cuisine" => {
+                                let Some(cuisine) = li
+                                    .find(Class("tasty-recipes-cuisine"))
+                                    .next()
+                                    .map(|n| n.text())
+                                else {
+                                    continue;
+                                };
+                                self.cuisine = cuisine;
+                                self.trace.record(
+                                    "cuisine",
+                                    ".tasty-recipes-cuisine",
+                                    Some(&self.cuisine),
+                                    None,
+                                );
+                            }
+                            "category" => {
+                                let Some(category) = li
+                                    .find(Class("tasty-recipes-category"))
+                                    .next()
+                                    .map(|n| n.text())
+                                else {
+                                    continue;
+                                };
+                                self.category = category;
+                                self.trace.record(
+                                    "category",
+                                    ".tasty-recipes-category",
+                                    Some(&self.category),
+                                    None,
+                                );
+                            }
+                            "method" => {
+                                let Some(method) = li
+                                    .find(Class("tasty-recipes-method"))
+                                    .next()
+                                    .map(|n| n.text())
+                                else {
+                                    continue;
+                                };
+                                self.method = method;
+                                self.trace.record(
+                                    "method",
+                                    ".tasty-recipes-method",
+                                    Some(&self.method),
+                                    None,
+                                );
+                            }
+
+                            _ => {}
+                        },
+                        None => {}
+                    }
+                }
+            }
+
+            if let Some(nutrition_url) = body
+                .find(Name("iframe").and(Attr("title", "nutritional information")))
+                .next()
+                .and_then(|nut| nut.attr("data-l-src"))
+            {
+                self.trace.record(
+                    "macros",
+                    "iframe[title='nutritional information'][data-l-src]",
+                    Some(nutrition_url),
+                    Some("Nutrifox label, normalized by servings".to_string()),
+                );
+                self.get_macros(fetcher, format!("https:{}", nutrition_url).as_str())
+                    .await?;
+            } else if let Some(m) = first_match(&body, tasty::NUTRITION) {
+                self.macros = Macros::from_text(&m.node.text());
+                self.trace.record_match(
+                    "macros",
+                    &m,
+                    None,
+                    self.macros
+                        .as_ref()
+                        .map(|_| "Macros::from_text".to_string()),
+                );
+            } else {
+                self.macros = None
+            }
+
+            Ok(())
+        } else {
+            Err(ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!(
+                    "Recipe card not found (after jump link was found): {}",
+                    self.url
+                ),
+            ))?
+        }
+    }
+
+    /// Reads a card time such as "1 hour 30 minutes" into minutes.
+    ///
+    /// # Errors
+    ///
+    /// Returns `E_PARSE` if the time is unreadable or too large, so one bad card fails
+    /// its recipe instead of the whole crawl.
+    fn parse_time(&self, field: &str, time_str: &str) -> Result<u32, Box<dyn Error>> {
+        u32::from_time_str(time_str).map_err(|e| {
+            ScrapeError::new(
+                ErrorCode::Parse,
+                format!(
+                    "Invalid {} {:?} for {}: {}",
+                    field,
+                    time_str.trim(),
+                    self.url,
+                    e
+                ),
+            )
+            .into()
+        })
+    }
+
+    /// Parses the recipe's name and total time from the header node.
+    ///
+    /// Extracts the name and total time using the `tasty::NAME` and `tasty::TOTAL_TIME`
+    /// selector chains, updating the `Recipe` struct's fields. A missing total time is
+    /// left at zero; an unreadable one is an `E_PARSE` error.
+    ///
+    /// # Arguments
+    ///
+    /// - `header`: The HTML node containing the recipe's header information.
+    ///
+    /// # Returns
+    ///
+    /// - `Ok(())` if parsing is successful, or an error if parsing fails.    
+    fn parse_header(&mut self, header: &Node) -> Result<(), Box<dyn Error>> {
+        let name = first_match(header, tasty::NAME).ok_or_else(|| {
+            ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!("No recipe name found for: {}", self.url),
+            )
+        })?;
+        self.name = name.node.text();
+        self.trace
+            .record_match("name", &name, Some(&self.name), None);
+
+        if let Some(m) = first_match(header, tasty::TOTAL_TIME) {
+            let time_str = m.node.text();
+
+            self.total_time = self.parse_time("total_time", &time_str)?;
+            self.trace.record_match(
+                "total_time",
+                &m,
+                Some(&time_str),
+                Some(format!("from_time_str -> {} min", self.total_time)),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Parses the ingredient list from a given HTML node, extracting ingredient names, quantities, units, and preparation details.
+    /// The ingredients are then added to the recipe's list of ingredients.
+    ///
+    /// # Arguments
+    /// - `list`: The HTML node containing the ingredient list (`<ul>` or `<li>` elements).
+    ///
+    /// # Returns
+    /// A `Result` that indicates whether the parsing succeeded (`Ok(())`) or failed (`Err`).    
+    pub(in crate::recipes) fn parse_ingredients(
+        &mut self,
+        list: &Node,
+    ) -> Result<(), Box<dyn Error>> {
+        let mut ingredients = Vec::new();
+
+        for ingredient in list.find(Name("li")) {
+            let name = match ingredient.find(Name("strong")).next() {
+                Some(n) => n.text(),
+                None => match ingredient.find(Name("b")).next() {
+                    Some(n) => n.text(),
+                    None => Err(ScrapeError::new(
+                        ErrorCode::SelectorMissing,
+                        format!(
+                            "Error building ingredients for: {}. No ingredient name found:{} ",
+                            self.url,
+                            ingredient.text()
+                        ),
+                    ))?,
+                },
+            };
+
+            let (brand, name) = brand::split_brand(&normalize_text(&name));
+            let raw = ingredient.text().trim().to_string();
+            let (optional, to_taste) = Ingredient::flags(&normalize_text(&raw));
+            let sub_recipe = sub_recipe::link(&ingredient, &self.url);
+            self.trace.record(
+                "ingredients",
+                ".tasty-recipes-ingredients li strong, b",
+                Some(&raw),
+                brand
+                    .as_ref()
+                    .map(|b| format!("split_brand -> {:?} + {:?}", b, name)),
+            );
+
+            if let Some(span) = ingredient.find(Name("span")).nth(1) {
+                let quantity = match span.attr("data-amount") {
+                    Some(q) => q.parse::<f32>()?,
+                    None => span
+                        .find(Name("span"))
+                        .next()
+                        .ok_or("Could not parse inner span")?
+                        .attr("data-amount")
+                        .ok_or("Could not parse inner span")?
+                        .parse::<f32>()?,
+                };
+
+                let units = span.attr("data-unit").and_then(|u| match Unit::from(u) {
+                    Ok(u) => Some(u),
+                    Err(_) => None, // just return none if the parsing fails
+                });
+
+                let prepped = ingredient
+                    .find(Name("em"))
+                    .next()
+                    .and_then(|p| Some(normalize_text(&p.text())));
+
+                ingredients.push(Ingredient {
+                    name,
+                    brand,
+                    quantity,
+                    units,
+                    prepped,
+                    optional,
+                    to_taste,
+                    sub_recipe,
+                    raw,
+                });
+            } else {
+                // no units (things like parsley, optional for seriving: https://www.aheadofthyme.com/minestrone-soup/)
+                let prepped = ingredient
+                    .find(Name("em"))
+                    .next()
+                    .and_then(|p| Some(normalize_text(&p.text())));
+
+                ingredients.push(Ingredient {
+                    name,
+                    brand,
+                    quantity: 0.0,
+                    units: None,
+                    prepped,
+                    optional,
+                    to_taste,
+                    sub_recipe,
+                    raw,
+                });
+            }
+        }
+
+        self.add_ingredients(ingredients);
+
+        Ok(())
+    }
+
+    /// Parses the instructions from the provided HTML node and stores them in the recipe.
+    /// It handles both single and multiple ordered lists for steps and organizes them under section headers.
+    ///
+    /// # Arguments
+    /// - `list`: The HTML node containing the instructions to be parsed.
+    ///
+    /// The steps are read from the `tasty-recipes-instructions-body` div, which may be
+    /// `list` itself when the selector chain fell back to it. A body with no `ol` lists
+    /// is read as a single section of its `li` items.
+    ///
+    /// # Returns
+    /// - `Result<(), Box<dyn Error>>`: Returns `Ok(())` on success, or an error if parsing fails.
+    ///
+    /// # Errors
+    ///
+    /// Returns `E_SELECTOR_MISSING` if no instructions body or steps are found.
+    fn parse_instructions(&mut self, list: &Node) -> Result<(), Box<dyn Error>> {
+        // "https://www.aheadofthyme.com/easy-meat-lasagna/" for some reason not grabbing all instructions, but other similar examples are
+        let h4_blocks: Vec<_> = list.find(Name("h4")).collect();
+
+        let missing = || {
+            ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!("No instruction steps found for: {}", self.url),
+            )
+        };
+
+        let body = if list.is(Class("tasty-recipes-instructions-body")) {
+            Some(*list)
+        } else {
+            list.find(Class("tasty-recipes-instructions-body"))
+                .next()
+                .or_else(|| list.find(Name("div")).nth(1))
+        }
+        .ok_or_else(missing)?;
+
+        let mut ol_blocks: Vec<_> = body
+            .children()
+            .filter(|child| child.name() == Some("ol"))
+            .collect();
+
+        if ol_blocks.is_empty() {
+            let steps: Vec<String> = body.find(Name("li")).map(|step| step.text()).collect();
+            if steps.is_empty() {
+                return Err(missing().into());
+            }
+
+            self.instructions = vec![Instruction {
+                section: None,
+                steps,
+                anchors: Vec::new(),
+            }];
+        } else if ol_blocks.len() == 1 {
+            // we have 1 ol block
+            let ol_block = ol_blocks
+                .pop()
+                .expect("Ol blocks failed to pop the element");
+
+            self.instructions = vec![Instruction {
+                section: None,
+                steps: ol_block.find(Name("li")).map(|step| step.text()).collect(),
+                anchors: Vec::new(),
+            }];
+        } else {
+            let mut instructions = Vec::new();
+
+            for (h4, ol) in h4_blocks.iter().zip(ol_blocks.iter()) {
+                let instruction = Instruction {
+                    section: Some(String::from(h4.text().trim_end_matches(":"))),
+                    steps: ol.find(Name("li")).map(|step| step.text()).collect(),
+                    anchors: Vec::new(),
+                };
+                instructions.push(instruction);
+            }
+
+            self.instructions = instructions;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_instructions_fallback_body() {
+        let document = Document::from(
+            r#"<div class="tasty-recipes-instructions-body">
+                <ol><li>Boil the noodles.</li><li>Layer and bake.</li></ol>
+            </div>"#,
+        );
+        let body = document
+            .find(Class("tasty-recipes-instructions-body"))
+            .next()
+            .unwrap();
+
+        let mut r = Recipe::default();
+        r.parse_instructions(&body)
+            .expect("Failed to parse instructions");
+        assert_eq!(r.instructions.len(), 1);
+        assert_eq!(
+            r.instructions[0].steps,
+            vec!["Boil the noodles.", "Layer and bake."]
+        );
+
+        let document =
+            Document::from(r#"<div class="tasty-recipes-instructions"><p>See video.</p></div>"#);
+        let list = document
+            .find(Class("tasty-recipes-instructions"))
+            .next()
+            .unwrap();
+        let e = Recipe::default()
+            .parse_instructions(&list)
+            .expect_err("Expected a missing selector error");
+        assert_eq!(ErrorCode::of(&*e), ErrorCode::SelectorMissing);
+    }
+
+    #[test]
+    fn test_parse_header_bad_time() {
+        let document = Document::from(
+            r#"<header>
+                <h2 class="tasty-recipes-title">Easy Meat Lasagna</h2>
+                <span class="tasty-recipes-total-time">99999999 hours</span>
+            </header>"#,
+        );
+        let header = document.find(Name("header")).next().unwrap();
+
+        let mut r = Recipe::default();
+        let e = r
+            .parse_header(&header)
+            .expect_err("An overflowing time should fail the recipe");
+        assert_eq!(ErrorCode::of(&*e), ErrorCode::Parse);
+        assert_eq!(r.total_time, 0);
+
+        let ok = Document::from(
+            r#"<header>
+                <h2 class="tasty-recipes-title">Easy Meat Lasagna</h2>
+                <span class="tasty-recipes-total-time">1 hour 30 minutes</span>
+            </header>"#,
+        );
+        let header = ok.find(Name("header")).next().unwrap();
+        r.parse_header(&header).expect("Failed to parse header");
+        assert_eq!(r.total_time, 90);
+    }
+}
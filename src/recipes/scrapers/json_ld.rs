@@ -0,0 +1,433 @@
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Name, Predicate};
+use serde_json::Value;
+use std::error::Error;
+
+use super::{ScrapeFuture, SiteScraper};
+use crate::error::{ErrorCode, ScrapeError};
+use crate::recipes::fetch::Fetcher;
+use crate::recipes::selectors::Match;
+use crate::recipes::{brand, Ingredient, Instruction, Macros, Recipe};
+use crate::utils::{normalize_text, U32Ext};
+
+/// Confidence given to fields read from JSON-LD. The data is structured, but written
+/// by the site's SEO plugin rather than the recipe card, so it can lag behind the page.
+pub const JSON_LD_CONFIDENCE: f32 = 0.9;
+
+const SELECTOR: &str = "script[type='application/ld+json']";
+
+/// Scrapes the schema.org `Recipe` object most recipe plugins embed as JSON-LD. Claims
+/// no site by URL, only pages that carry the data.
+pub struct JsonLdScraper;
+
+impl SiteScraper for JsonLdScraper {
+    fn name(&self) -> &'static str {
+        "json-ld"
+    }
+
+    fn matches(&self, _url: &str) -> bool {
+        false
+    }
+
+    fn detects(&self, document: &Document) -> bool {
+        find_recipe(document).is_some()
+    }
+
+    fn parse<'a>(
+        &'a self,
+        recipe: &'a mut Recipe,
+        document: &'a Document,
+        _fetcher: &'a dyn Fetcher,
+    ) -> ScrapeFuture<'a> {
+        Box::pin(async move { recipe.parse_json_ld(document) })
+    }
+}
+
+/// Whether a JSON-LD object's `@type` (a string or an array of them) includes `Recipe`.
+fn is_recipe(object: &Value) -> bool {
+    match object.get("@type") {
+        Some(Value::String(t)) => t == "Recipe",
+        Some(Value::Array(types)) => types.iter().any(|t| t == "Recipe"),
+        _ => false,
+    }
+}
+
+/// Finds the `Recipe` object in a JSON-LD value, which may be the object itself, an
+/// array of objects, or a `@graph` of them (as Yoast writes it).
+fn recipe_object(value: &Value) -> Option<&Value> {
+    match value {
+        Value::Array(items) => items.iter().find_map(recipe_object),
+        Value::Object(_) if is_recipe(value) => Some(value),
+        Value::Object(_) => value.get("@graph").and_then(recipe_object),
+        _ => None,
+    }
+}
+
+/// Finds the first JSON-LD script on the page holding a `Recipe`, skipping scripts that
+/// are not valid JSON.
+///
+/// # Returns
+///
+/// - The script node and the `Recipe` object.
+fn find_recipe(document: &Document) -> Option<(Node<'_>, Value)> {
+    document
+        .find(Name("script").and(Attr("type", "application/ld+json")))
+        .find_map(|script| {
+            let value: Value = serde_json::from_str(&script.text()).ok()?;
+            recipe_object(&value)
+                .cloned()
+                .map(|recipe| (script, recipe))
+        })
+}
+
+fn json_ld_match<'a>(node: Node<'a>, key: &str) -> Match<'a> {
+    Match {
+        node,
+        selector: format!("{} {}", SELECTOR, key),
+        confidence: JSON_LD_CONFIDENCE,
+    }
+}
+
+/// The text of a value that may be a string, a number or an array of either.
+fn text(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(normalize_text(s)).filter(|s| !s.is_empty()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Array(items) => {
+            let texts: Vec<String> = items.iter().filter_map(text).collect();
+            Some(texts.join(", ")).filter(|s| !s.is_empty())
+        }
+        _ => None,
+    }
+}
+
+/// The URL of a value that may be a URL, an `ImageObject`/`VideoObject`, or an array of
+/// either, in which case the first is used.
+fn url(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.trim().to_string()),
+        Value::Array(items) => items.iter().find_map(url),
+        Value::Object(_) => ["contentUrl", "embedUrl", "url"]
+            .iter()
+            .find_map(|key| value.get(*key).and_then(url)),
+        _ => None,
+    }
+}
+
+/// The steps of a `HowToStep`, a plain string, or a list of either.
+fn steps(value: &Value) -> Vec<String> {
+    match value {
+        Value::String(s) => s
+            .lines()
+            .map(normalize_text)
+            .filter(|s| !s.is_empty())
+            .collect(),
+        Value::Array(items) => items.iter().flat_map(steps).collect(),
+        Value::Object(_) => value
+            .get("text")
+            .or_else(|| value.get("name"))
+            .and_then(text)
+            .into_iter()
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+/// Groups `recipeInstructions` into sections. `HowToSection`s become named sections;
+/// steps outside any section are collected into unnamed ones between them.
+fn instructions(value: &Value) -> Vec<Instruction> {
+    let items = match value {
+        Value::Array(items) => items.as_slice(),
+        other => std::slice::from_ref(other),
+    };
+
+    let mut sections: Vec<Instruction> = Vec::new();
+    for item in items {
+        if item.get("@type").is_some_and(|t| t == "HowToSection") {
+            sections.push(Instruction {
+                section: item.get("name").and_then(text),
+                steps: item.get("itemListElement").map(steps).unwrap_or_default(),
+                anchors: Vec::new(),
+            });
+            continue;
+        }
+
+        match sections.last_mut() {
+            Some(last) if last.section.is_none() => last.steps.extend(steps(item)),
+            _ => sections.push(Instruction {
+                section: None,
+                steps: steps(item),
+                anchors: Vec::new(),
+            }),
+        }
+    }
+
+    sections.retain(|s| !s.steps.is_empty());
+    sections
+}
+
+/// The leading number of a `recipeYield` such as `4`, `"4 servings"` or `["4", "4 bowls"]`.
+fn servings(value: &Value) -> Option<u64> {
+    match value {
+        Value::Number(n) => n.as_u64(),
+        Value::String(s) => s
+            .split(|c: char| !c.is_ascii_digit())
+            .find(|part| !part.is_empty())?
+            .parse()
+            .ok(),
+        Value::Array(items) => items.iter().find_map(servings),
+        _ => None,
+    }
+}
+
+impl Recipe {
+    /// Parses the page's schema.org `Recipe` JSON-LD. Used for sites without a dedicated
+    /// scraper, and before the heuristic extractor when a known card is missing.
+    ///
+    /// Ingredients are kept as written (`quantity` 0, no units), since JSON-LD gives
+    /// each one as a single string. Durations are read as ISO 8601; any that do not
+    /// parse are left unset rather than failing the recipe.
+    ///
+    /// # Errors
+    ///
+    /// Returns `E_SELECTOR_MISSING` if the page has no JSON-LD `Recipe`.
+    pub(in crate::recipes) fn parse_json_ld(
+        &mut self,
+        document: &Document,
+    ) -> Result<(), Box<dyn Error>> {
+        let (script, recipe) = find_recipe(document).ok_or_else(|| {
+            ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!("No JSON-LD recipe found for: {}", self.url),
+            )
+        })?;
+
+        if let Some(name) = recipe.get("name").and_then(text) {
+            self.name = name;
+            self.trace.record_match(
+                "name",
+                &json_ld_match(script, "name"),
+                Some(&self.name),
+                None,
+            );
+        }
+
+        if let Some(description) = recipe.get("description").and_then(text) {
+            self.description = Some(description);
+            self.trace.record_match(
+                "description",
+                &json_ld_match(script, "description"),
+                self.description.as_deref(),
+                None,
+            );
+        }
+
+        for (key, field) in [
+            ("prepTime", "prep_time"),
+            ("cookTime", "cook_time"),
+            ("totalTime", "total_time"),
+        ] {
+            let Some(raw) = recipe.get(key).and_then(Value::as_str) else {
+                continue;
+            };
+            let Ok(minutes) = u32::from_iso_duration(raw) else {
+                continue;
+            };
+            match field {
+                "prep_time" => self.prep_time = minutes,
+                "cook_time" => self.cook_time = minutes,
+                _ => self.total_time = minutes,
+            }
+            self.trace.record_match(
+                field,
+                &json_ld_match(script, key),
+                Some(raw),
+                Some(format!("from_iso_duration -> {} min", minutes)),
+            );
+        }
+
+        let mut parsed = Vec::new();
+        for raw in recipe
+            .get("recipeIngredient")
+            .and_then(Value::as_array)
+            .into_iter()
+            .flatten()
+            .filter_map(Value::as_str)
+        {
+            let text = normalize_text(raw);
+            if text.is_empty() {
+                continue;
+            }
+
+            let (brand, name) = brand::split_brand(&text);
+            let (optional, to_taste) = Ingredient::flags(&text);
+            parsed.push(Ingredient {
+                name,
+                brand,
+                quantity: 0.0,
+                units: None,
+                prepped: None,
+                optional,
+                to_taste,
+                sub_recipe: None,
+                raw: raw.trim().to_string(),
+            });
+        }
+        self.trace.record_match(
+            "ingredients",
+            &json_ld_match(script, "recipeIngredient"),
+            None,
+            Some(format!("{} ingredients", parsed.len())),
+        );
+        self.add_ingredients(parsed);
+
+        if let Some(value) = recipe.get("recipeInstructions") {
+            self.instructions = instructions(value);
+            self.trace.record_match(
+                "instructions",
+                &json_ld_match(script, "recipeInstructions"),
+                None,
+                Some(format!(
+                    "{} sections, {} steps",
+                    self.instructions.len(),
+                    self.instructions
+                        .iter()
+                        .map(|i| i.steps.len())
+                        .sum::<usize>()
+                )),
+            );
+        }
+
+        if let Some(servings) = recipe.get("recipeYield").and_then(servings) {
+            self.servings = servings;
+            self.trace.record_match(
+                "servings",
+                &json_ld_match(script, "recipeYield"),
+                Some(&servings.to_string()),
+                None,
+            );
+        }
+
+        if let Some(cuisine) = recipe.get("recipeCuisine").and_then(text) {
+            self.cuisine = cuisine;
+            self.trace.record_match(
+                "cuisine",
+                &json_ld_match(script, "recipeCuisine"),
+                Some(&self.cuisine),
+                None,
+            );
+        }
+
+        if let Some(category) = recipe.get("recipeCategory").and_then(text) {
+            self.category = category;
+            self.trace.record_match(
+                "category",
+                &json_ld_match(script, "recipeCategory"),
+                Some(&self.category),
+                None,
+            );
+        }
+
+        if self.img.is_empty() {
+            if let Some(img) = recipe.get("image").and_then(url) {
+                self.img = img;
+                self.trace.record_match(
+                    "img",
+                    &json_ld_match(script, "image"),
+                    Some(&self.img),
+                    None,
+                );
+            }
+        }
+
+        if let Some(video) = recipe.get("video").and_then(url) {
+            self.video = Some(video);
+            self.trace.record_match(
+                "video",
+                &json_ld_match(script, "video"),
+                self.video.as_deref(),
+                None,
+            );
+        }
+
+        if let Some(macros) = recipe.get("nutrition").and_then(Macros::from_json_ld) {
+            self.macros = Some(macros);
+            self.trace.record_match(
+                "macros",
+                &json_ld_match(script, "nutrition"),
+                None,
+                Some("Macros::from_json_ld".to_string()),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><head>
+        <script type="application/ld+json">{"@context": "https://schema.org", "@type": "WebSite"}</script>
+        <script type="application/ld+json">{"@context": "https://schema.org", "@graph": [
+            {"@type": "WebPage", "name": "Soup | Example"},
+            {"@type": ["Recipe"], "name": "Tomato Soup",
+             "description": "A weeknight soup.",
+             "image": [{"@type": "ImageObject", "url": "https://example.com/soup.jpg"}],
+             "prepTime": "PT10M", "cookTime": "PT1H", "totalTime": "P0DT1H10M",
+             "recipeYield": ["4", "4 bowls"],
+             "recipeCuisine": ["Italian", "American"],
+             "recipeCategory": "Soup",
+             "recipeIngredient": ["2 cans crushed tomatoes", "1 onion", "salt, to taste"],
+             "recipeInstructions": [
+                {"@type": "HowToStep", "text": "Sweat the onion."},
+                {"@type": "HowToSection", "name": "Finish", "itemListElement": [
+                    {"@type": "HowToStep", "text": "Add the tomatoes."},
+                    {"@type": "HowToStep", "text": "Simmer 20 minutes."}
+                ]}
+             ],
+             "nutrition": {"@type": "NutritionInformation", "calories": "210 kcal",
+                "fatContent": "7 g", "carbohydrateContent": "30 g", "proteinContent": "6 g"}}
+        ]}</script>
+    </head><body><h1>Tomato Soup</h1></body></html>"#;
+
+    #[test]
+    fn test_parse_json_ld() {
+        let document = Document::from(PAGE);
+        assert!(JsonLdScraper.detects(&document));
+
+        let mut r = Recipe::default();
+        r.parse_json_ld(&document).expect("Failed to parse JSON-LD");
+
+        assert_eq!(r.name, "Tomato Soup");
+        assert_eq!(r.img, "https://example.com/soup.jpg");
+        assert_eq!((r.prep_time, r.cook_time, r.total_time), (10, 60, 70));
+        assert_eq!(r.servings, 4);
+        assert_eq!(r.cuisine, "Italian, American");
+        assert_eq!(
+            r.ingredient_names().collect::<Vec<_>>(),
+            vec!["2 cans crushed tomatoes", "1 onion", "salt, to taste"]
+        );
+        assert!(r.ingredients[2].to_taste);
+
+        assert_eq!(r.instructions.len(), 2);
+        assert_eq!(r.instructions[0].section, None);
+        assert_eq!(r.instructions[1].section.as_deref(), Some("Finish"));
+        assert_eq!(r.instructions[1].steps.len(), 2);
+
+        assert!(r.macros.is_some());
+        assert_eq!(r.trace.confidence(), Some(JSON_LD_CONFIDENCE));
+    }
+
+    #[test]
+    fn test_no_json_ld_recipe() {
+        let document = Document::from(
+            r#"<script type="application/ld+json">{"@type": "WebSite"}</script>
+               <script type="application/ld+json">not json</script>"#,
+        );
+        assert!(!JsonLdScraper.detects(&document));
+        assert!(Recipe::default().parse_json_ld(&document).is_err());
+    }
+}
@@ -0,0 +1,439 @@
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Attr, Name};
+use serde_json::{Map, Value};
+use std::error::Error;
+
+use super::{ScrapeFuture, SiteScraper};
+use crate::error::{ErrorCode, ScrapeError};
+use crate::recipes::fetch::Fetcher;
+use crate::recipes::selectors::Match;
+use crate::recipes::{brand, sub_recipe, Ingredient, Instruction, Macros, NutritionSource, Recipe};
+use crate::utils::{normalize_text, U32Ext};
+
+/// Confidence given to fields read from microdata. Properties are sprinkled through
+/// the page's HTML, so stray or duplicated ones are more common than in JSON-LD.
+pub const MICRODATA_CONFIDENCE: f32 = 0.8;
+
+/// Scrapes recipes marked up with schema.org microdata (`itemscope`, `itemprop`), as
+/// older sites and plugins do. Claims no site by URL, only pages that carry the markup.
+pub struct MicrodataScraper;
+
+impl SiteScraper for MicrodataScraper {
+    fn name(&self) -> &'static str {
+        "microdata"
+    }
+
+    fn matches(&self, _url: &str) -> bool {
+        false
+    }
+
+    fn detects(&self, document: &Document) -> bool {
+        find_scope(document, "Recipe").is_some()
+    }
+
+    fn parse<'a>(
+        &'a self,
+        recipe: &'a mut Recipe,
+        document: &'a Document,
+        _fetcher: &'a dyn Fetcher,
+    ) -> ScrapeFuture<'a> {
+        Box::pin(async move { recipe.parse_microdata(document) })
+    }
+}
+
+/// Whether an item's `itemtype` is the schema.org type `kind`.
+fn is_type(node: &Node, kind: &str) -> bool {
+    node.attr("itemtype").is_some_and(|types| {
+        types
+            .split_whitespace()
+            .any(|t| t.trim_end_matches('/').rsplit('/').next() == Some(kind))
+    })
+}
+
+/// The first item of type `kind` on the page.
+fn find_scope<'a>(document: &'a Document, kind: &str) -> Option<Node<'a>> {
+    document
+        .find(Attr("itemscope", ()))
+        .find(|n| is_type(n, kind))
+}
+
+/// The item a property belongs to: its nearest `itemscope` ancestor.
+fn owner<'a>(node: &Node<'a>) -> Option<Node<'a>> {
+    let mut parent = node.parent();
+    while let Some(p) = parent {
+        if p.attr("itemscope").is_some() {
+            return Some(p);
+        }
+        parent = p.parent();
+    }
+    None
+}
+
+/// The `prop` properties of `item`, skipping those of items nested inside it.
+fn props<'a>(item: Node<'a>, prop: &'a str) -> impl Iterator<Item = Node<'a>> + 'a {
+    item.find(Attr("itemprop", ())).filter(move |n| {
+        n.attr("itemprop")
+            .is_some_and(|p| p.split_whitespace().any(|p| p == prop))
+            && owner(n).is_some_and(|o| o.index() == item.index())
+    })
+}
+
+/// The first `prop` property of `item`.
+fn prop<'a>(item: Node<'a>, prop: &'a str) -> Option<Node<'a>> {
+    props(item, prop).next()
+}
+
+/// A property's value, read from the attribute microdata defines for its element.
+fn value(node: &Node) -> String {
+    let attr = match node.name() {
+        Some("meta") => node.attr("content"),
+        Some("a") | Some("link") | Some("area") => node.attr("href"),
+        Some("img") | Some("audio") | Some("video") | Some("source") | Some("iframe")
+        | Some("embed") => node.attr("src"),
+        Some("time") => node.attr("datetime"),
+        Some("data") | Some("meter") => node.attr("value"),
+        _ => node.attr("content"),
+    };
+
+    match attr {
+        Some(attr) => attr.trim().to_string(),
+        None => normalize_text(&node.text()),
+    }
+}
+
+fn microdata_match<'a>(node: Node<'a>, prop: &str) -> Match<'a> {
+    Match {
+        node,
+        selector: format!("[itemprop='{}']", prop),
+        confidence: MICRODATA_CONFIDENCE,
+    }
+}
+
+/// The steps of a `recipeInstructions` property: a `HowToStep` item, a list, or a block
+/// of text with one step per line.
+fn steps(node: Node) -> Vec<String> {
+    if node.attr("itemscope").is_some() {
+        let text = prop(node, "text").map_or_else(|| node.text(), |t| value(&t));
+        return vec![normalize_text(&text)];
+    }
+
+    let items: Vec<String> = node
+        .find(Name("li"))
+        .map(|li| normalize_text(&li.text()))
+        .collect();
+    let items = if items.is_empty() {
+        node.text().lines().map(normalize_text).collect()
+    } else {
+        items
+    };
+
+    items.into_iter().filter(|s| !s.is_empty()).collect()
+}
+
+/// Groups the recipe's `recipeInstructions` into sections, the same way JSON-LD ones
+/// are: `HowToSection` items become named sections, other steps fill unnamed ones.
+fn instructions(item: Node) -> Vec<Instruction> {
+    let mut sections: Vec<Instruction> = Vec::new();
+    for node in props(item, "recipeInstructions") {
+        if is_type(&node, "HowToSection") {
+            sections.push(Instruction {
+                section: prop(node, "name").map(|n| value(&n)),
+                steps: props(node, "itemListElement").flat_map(steps).collect(),
+                anchors: Vec::new(),
+            });
+            continue;
+        }
+
+        match sections.last_mut() {
+            Some(last) if last.section.is_none() => last.steps.extend(steps(node)),
+            _ => sections.push(Instruction {
+                section: None,
+                steps: steps(node),
+                anchors: Vec::new(),
+            }),
+        }
+    }
+
+    sections.retain(|s| !s.steps.is_empty());
+    sections
+}
+
+/// Reads a `NutritionInformation` item into the JSON shape `Macros::from_json_ld` takes.
+fn nutrition(item: Node) -> Option<Macros> {
+    let mut object = Map::new();
+    for node in item.find(Attr("itemprop", ())) {
+        if owner(&node).is_some_and(|o| o.index() == item.index()) {
+            for name in node.attr("itemprop").unwrap_or_default().split_whitespace() {
+                object.insert(name.to_string(), Value::String(value(&node)));
+            }
+        }
+    }
+
+    let mut macros = Macros::from_json_ld(&Value::Object(object))?;
+    macros.source = NutritionSource::Microdata;
+    macros.confidence = macros.source.confidence();
+    Some(macros)
+}
+
+impl Recipe {
+    /// Parses the page's schema.org `Recipe` microdata. Used for sites without a
+    /// dedicated scraper or JSON-LD, before falling back to the heuristic extractor.
+    ///
+    /// Like JSON-LD, ingredients are kept as written (`quantity` 0, no units), and
+    /// durations that are not ISO 8601 are left unset. The pre-2015 `ingredients`
+    /// property is read as well as `recipeIngredient`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `E_SELECTOR_MISSING` if the page has no microdata `Recipe`.
+    pub(in crate::recipes) fn parse_microdata(
+        &mut self,
+        document: &Document,
+    ) -> Result<(), Box<dyn Error>> {
+        let item = find_scope(document, "Recipe").ok_or_else(|| {
+            ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!("No microdata recipe found for: {}", self.url),
+            )
+        })?;
+
+        if let Some(node) = prop(item, "name") {
+            self.name = value(&node);
+            self.trace.record_match(
+                "name",
+                &microdata_match(node, "name"),
+                Some(&self.name),
+                None,
+            );
+        }
+
+        if let Some(node) = prop(item, "description") {
+            self.description = Some(value(&node));
+            self.trace.record_match(
+                "description",
+                &microdata_match(node, "description"),
+                self.description.as_deref(),
+                None,
+            );
+        }
+
+        for (key, field) in [
+            ("prepTime", "prep_time"),
+            ("cookTime", "cook_time"),
+            ("totalTime", "total_time"),
+        ] {
+            let Some(node) = prop(item, key) else {
+                continue;
+            };
+            let raw = value(&node);
+            let Ok(minutes) = u32::from_iso_duration(&raw) else {
+                continue;
+            };
+            match field {
+                "prep_time" => self.prep_time = minutes,
+                "cook_time" => self.cook_time = minutes,
+                _ => self.total_time = minutes,
+            }
+            self.trace.record_match(
+                field,
+                &microdata_match(node, key),
+                Some(&raw),
+                Some(format!("from_iso_duration -> {} min", minutes)),
+            );
+        }
+
+        let mut parsed = Vec::new();
+        for node in props(item, "recipeIngredient").chain(props(item, "ingredients")) {
+            let raw = value(&node);
+            let text = normalize_text(&raw);
+            if text.is_empty() {
+                continue;
+            }
+
+            let (brand, name) = brand::split_brand(&text);
+            let (optional, to_taste) = Ingredient::flags(&text);
+            parsed.push(Ingredient {
+                name,
+                brand,
+                quantity: 0.0,
+                units: None,
+                prepped: None,
+                optional,
+                to_taste,
+                sub_recipe: sub_recipe::link(&node, &self.url),
+                raw,
+            });
+        }
+        if let Some(node) = prop(item, "recipeIngredient").or_else(|| prop(item, "ingredients")) {
+            self.trace.record_match(
+                "ingredients",
+                &microdata_match(node, "recipeIngredient"),
+                None,
+                Some(format!("{} ingredients", parsed.len())),
+            );
+        }
+        self.add_ingredients(parsed);
+
+        if let Some(node) = prop(item, "recipeInstructions") {
+            self.instructions = instructions(item);
+            self.trace.record_match(
+                "instructions",
+                &microdata_match(node, "recipeInstructions"),
+                None,
+                Some(format!(
+                    "{} sections, {} steps",
+                    self.instructions.len(),
+                    self.instructions
+                        .iter()
+                        .map(|i| i.steps.len())
+                        .sum::<usize>()
+                )),
+            );
+        }
+
+        if let Some(node) = prop(item, "recipeYield") {
+            let raw = value(&node);
+            if let Some(servings) = raw
+                .split(|c: char| !c.is_ascii_digit())
+                .find(|part| !part.is_empty())
+                .and_then(|n| n.parse().ok())
+            {
+                self.servings = servings;
+                self.trace.record_match(
+                    "servings",
+                    &microdata_match(node, "recipeYield"),
+                    Some(&raw),
+                    None,
+                );
+            }
+        }
+
+        if let Some(node) = prop(item, "recipeCuisine") {
+            self.cuisine = value(&node);
+            self.trace.record_match(
+                "cuisine",
+                &microdata_match(node, "recipeCuisine"),
+                Some(&self.cuisine),
+                None,
+            );
+        }
+
+        if let Some(node) = prop(item, "recipeCategory") {
+            self.category = value(&node);
+            self.trace.record_match(
+                "category",
+                &microdata_match(node, "recipeCategory"),
+                Some(&self.category),
+                None,
+            );
+        }
+
+        if self.img.is_empty() {
+            if let Some(node) = prop(item, "image") {
+                self.img = prop(node, "url").map_or_else(|| value(&node), |u| value(&u));
+                self.trace.record_match(
+                    "img",
+                    &microdata_match(node, "image"),
+                    Some(&self.img),
+                    None,
+                );
+            }
+        }
+
+        if let Some(node) = prop(item, "video") {
+            self.video = Some(
+                prop(node, "contentUrl")
+                    .or_else(|| prop(node, "embedUrl"))
+                    .map_or_else(|| value(&node), |u| value(&u)),
+            );
+            self.trace.record_match(
+                "video",
+                &microdata_match(node, "video"),
+                self.video.as_deref(),
+                None,
+            );
+        }
+
+        if let Some((node, macros)) =
+            prop(item, "nutrition").and_then(|node| nutrition(node).map(|m| (node, m)))
+        {
+            self.macros = Some(macros);
+            self.trace.record_match(
+                "macros",
+                &microdata_match(node, "nutrition"),
+                None,
+                Some("Macros::from_json_ld".to_string()),
+            );
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PAGE: &str = r#"<html><body>
+        <div itemscope itemtype="http://schema.org/Recipe">
+            <h1 itemprop="name">Tomato Soup</h1>
+            <img itemprop="image" src="https://example.com/soup.jpg">
+            <p itemprop="description">A weeknight soup.</p>
+            <meta itemprop="prepTime" content="PT10M">
+            <time itemprop="cookTime" datetime="PT1H">1 hour</time>
+            <span itemprop="recipeYield">Serves 4</span>
+            <div itemprop="author" itemscope itemtype="http://schema.org/Person">
+                <span itemprop="name">Jane</span>
+            </div>
+            <ul>
+                <li itemprop="recipeIngredient">2 cans crushed tomatoes</li>
+                <li itemprop="ingredients">1 onion</li>
+            </ul>
+            <ol itemprop="recipeInstructions">
+                <li>Sweat the onion.</li>
+                <li>Add the tomatoes and simmer 20 minutes.</li>
+            </ol>
+            <div itemprop="nutrition" itemscope itemtype="http://schema.org/NutritionInformation">
+                <span itemprop="calories">210 kcal</span>
+                <span itemprop="fatContent">7 g</span>
+                <span itemprop="proteinContent">6 g</span>
+            </div>
+        </div>
+    </body></html>"#;
+
+    #[test]
+    fn test_parse_microdata() {
+        let document = Document::from(PAGE);
+        assert!(MicrodataScraper.detects(&document));
+
+        let mut r = Recipe::default();
+        r.parse_microdata(&document)
+            .expect("Failed to parse microdata");
+
+        // The author's name belongs to the nested Person, not the recipe
+        assert_eq!(r.name, "Tomato Soup");
+        assert_eq!(r.img, "https://example.com/soup.jpg");
+        assert_eq!((r.prep_time, r.cook_time), (10, 60));
+        assert_eq!(r.servings, 4);
+        assert_eq!(
+            r.ingredient_names().collect::<Vec<_>>(),
+            vec!["2 cans crushed tomatoes", "1 onion"]
+        );
+        assert_eq!(r.instructions.len(), 1);
+        assert_eq!(r.instructions[0].steps.len(), 2);
+
+        let macros = r.macros.as_ref().expect("No macros");
+        assert_eq!(macros.source, NutritionSource::Microdata);
+        assert_eq!(r.trace.confidence(), Some(MICRODATA_CONFIDENCE));
+    }
+
+    #[test]
+    fn test_no_microdata_recipe() {
+        let document = Document::from(
+            r#"<div itemscope itemtype="https://schema.org/Person"><span itemprop="name">Jane</span></div>"#,
+        );
+        assert!(!MicrodataScraper.detects(&document));
+        assert!(Recipe::default().parse_microdata(&document).is_err());
+    }
+}
@@ -0,0 +1,138 @@
+use reqwest::Url;
+use select::document::Document;
+use std::error::Error;
+use std::future::Future;
+use std::pin::Pin;
+
+use super::fetch::Fetcher;
+use super::Recipe;
+
+pub mod aheadofthyme;
+pub mod json_ld;
+pub mod microdata;
+pub mod wprm;
+
+pub use aheadofthyme::AheadOfThymeScraper;
+pub use json_ld::JsonLdScraper;
+pub use microdata::MicrodataScraper;
+pub use wprm::WprmScraper;
+
+/// The future returned by `SiteScraper::parse`, borrowing the recipe it fills in.
+pub type ScrapeFuture<'a> = Pin<Box<dyn Future<Output = Result<(), Box<dyn Error>>> + 'a>>;
+
+/// Parses recipes from one site, or one recipe plugin's markup.
+///
+/// Scrapers fill in a `Recipe` that already has its URL and image set; the fields
+/// derived from the parsed ones (spice level, methods, storage, ...) are filled in
+/// afterwards by `Recipe`, so scrapers only deal with the page.
+pub trait SiteScraper {
+    /// A short name for traces and logs.
+    fn name(&self) -> &'static str;
+
+    /// Whether this scraper is written for the site at `url`.
+    fn matches(&self, url: &str) -> bool;
+
+    /// Whether the page carries markup this scraper understands, for sites no scraper
+    /// claims by URL.
+    fn detects(&self, _document: &Document) -> bool {
+        false
+    }
+
+    /// Parses `document` into `recipe`, fetching any further page it needs (such as a
+    /// nutrition label) with `fetcher`.
+    fn parse<'a>(
+        &'a self,
+        recipe: &'a mut Recipe,
+        document: &'a Document,
+        fetcher: &'a dyn Fetcher,
+    ) -> ScrapeFuture<'a>;
+}
+
+/// The host of `url`, if it parses.
+fn host(url: &str) -> Option<String> {
+    Url::parse(url).ok()?.host_str().map(str::to_string)
+}
+
+/// An ordered set of scrapers. A page goes to the first scraper that matches its URL,
+/// or failing that, the first that detects its markup.
+pub struct Registry {
+    scrapers: Vec<Box<dyn SiteScraper>>,
+}
+
+impl Default for Registry {
+    /// A registry with every built-in scraper.
+    fn default() -> Self {
+        let mut registry = Registry::new();
+        registry.register(AheadOfThymeScraper);
+        registry.register(WprmScraper);
+        registry.register(JsonLdScraper);
+        registry.register(MicrodataScraper);
+        registry
+    }
+}
+
+impl Registry {
+    /// An empty registry.
+    pub fn new() -> Self {
+        Registry {
+            scrapers: Vec::new(),
+        }
+    }
+
+    /// Adds a scraper, tried after the ones already registered.
+    pub fn register(&mut self, scraper: impl SiteScraper + 'static) {
+        self.scrapers.push(Box::new(scraper));
+    }
+
+    /// Finds the scraper for a page.
+    pub fn find(&self, url: &str, document: &Document) -> Option<&dyn SiteScraper> {
+        self.scrapers
+            .iter()
+            .find(|s| s.matches(url))
+            .or_else(|| self.scrapers.iter().find(|s| s.detects(document)))
+            .map(|s| s.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_registry_find() {
+        let registry = Registry::default();
+        let plain = Document::from("<html><body><h1>Soup</h1></body></html>");
+        let tasty = Document::from(
+            r##"<a class="tasty-recipes-jump-link" href="#tasty-recipes-1-jump-target">Jump</a>"##,
+        );
+
+        let found = registry.find("https://www.aheadofthyme.com/easy-meat-lasagna/", &plain);
+        assert_eq!(found.map(|s| s.name()), Some("aheadofthyme"));
+
+        let found = registry.find("https://example.com/soup/", &tasty);
+        assert_eq!(found.map(|s| s.name()), Some("aheadofthyme"));
+
+        let wprm = Document::from(
+            r#"<div class="wprm-recipe-container"><script type="application/ld+json">{"@type": "Recipe"}</script></div>"#,
+        );
+        let found = registry.find("https://example.com/chili/", &wprm);
+        assert_eq!(found.map(|s| s.name()), Some("wprm"));
+
+        let json_ld = Document::from(
+            r#"<script type="application/ld+json">{"@type": "Recipe", "name": "Soup"}</script>"#,
+        );
+        let found = registry.find("https://example.com/soup/", &json_ld);
+        assert_eq!(found.map(|s| s.name()), Some("json-ld"));
+
+        let microdata = Document::from(
+            r#"<div itemscope itemtype="https://schema.org/Recipe"><h1 itemprop="name">Soup</h1></div>"#,
+        );
+        let found = registry.find("https://example.com/soup/", &microdata);
+        assert_eq!(found.map(|s| s.name()), Some("microdata"));
+
+        assert!(registry.find("https://example.com/soup/", &plain).is_none());
+        assert!(Registry::new()
+            .find("https://www.aheadofthyme.com/", &tasty)
+            .is_none());
+    }
+}
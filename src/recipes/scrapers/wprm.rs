@@ -0,0 +1,391 @@
+use select::document::Document;
+use select::node::Node;
+use select::predicate::{Class, Name, Predicate};
+use std::error::Error;
+
+use super::{ScrapeFuture, SiteScraper};
+use crate::error::{ErrorCode, ScrapeError};
+use crate::recipes::fetch::Fetcher;
+use crate::recipes::selectors::{first_match, wprm};
+use crate::recipes::{brand, sub_recipe, Ingredient, Instruction, Macros, Recipe, Unit};
+use crate::utils::normalize_text;
+
+/// Scrapes sites using the WP Recipe Maker card. Claims no site by URL, only pages
+/// that carry the card.
+pub struct WprmScraper;
+
+impl SiteScraper for WprmScraper {
+    fn name(&self) -> &'static str {
+        "wprm"
+    }
+
+    fn matches(&self, _url: &str) -> bool {
+        false
+    }
+
+    fn detects(&self, document: &Document) -> bool {
+        document
+            .find(Class("wprm-recipe-container").or(Class("wprm-recipe")))
+            .next()
+            .is_some()
+    }
+
+    fn parse<'a>(
+        &'a self,
+        recipe: &'a mut Recipe,
+        document: &'a Document,
+        _fetcher: &'a dyn Fetcher,
+    ) -> ScrapeFuture<'a> {
+        Box::pin(async move { recipe.parse_wprm_card(document) })
+    }
+}
+
+/// The first run of digits in `text`, e.g. `4` in "Serves 4" or `1` in "1 hour".
+fn leading_number(text: &str) -> Option<u32> {
+    text.split(|c: char| !c.is_ascii_digit())
+        .find(|part| !part.is_empty())?
+        .parse()
+        .ok()
+}
+
+/// Reads an ingredient amount such as `2`, `1 1/2`, `½`, `0.5` or `1-2` (the lower
+/// bound of a range is used).
+fn amount(text: &str) -> Option<f32> {
+    let text = normalize_text(text);
+    let lower = text.split(['-', '–']).next()?;
+
+    let mut total = None;
+    for part in lower.split_whitespace() {
+        let value = match part.split_once('/') {
+            Some((n, d)) => {
+                let d = d.parse::<f32>().ok().filter(|d| *d != 0.0)?;
+                n.parse::<f32>().ok()? / d
+            }
+            None => part.parse::<f32>().ok()?,
+        };
+        total = Some(total.unwrap_or(0.0) + value);
+    }
+    total
+}
+
+/// A WPRM duration, split over `wprm-recipe-<key>-days`, `-hours` and `-minutes`
+/// spans, in minutes. `None` if the card does not show it.
+fn duration(card: &Node, key: &str) -> Option<u32> {
+    let part = |unit: &str, scale: u32| {
+        card.find(Class(format!("wprm-recipe-{}-{}", key, unit).as_str()))
+            .next()
+            .and_then(|n| leading_number(&n.text()))
+            .map(|n| n.saturating_mul(scale))
+    };
+
+    let parts = [part("days", 24 * 60), part("hours", 60), part("minutes", 1)];
+    if parts.iter().all(Option::is_none) {
+        return None;
+    }
+    Some(
+        parts
+            .iter()
+            .flatten()
+            .fold(0u32, |t, p| t.saturating_add(*p)),
+    )
+}
+
+impl Recipe {
+    /// Parses a WP Recipe Maker card.
+    ///
+    /// Ingredients come with their amount, unit, name and notes in separate spans, so
+    /// they are read with quantities and units like Tasty Recipes ones. Instruction
+    /// groups become sections, and the nutrition container's text is read with
+    /// `Macros::from_text`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `E_SELECTOR_MISSING` if the page has no card, or an ingredient has no name.
+    pub(in crate::recipes) fn parse_wprm_card(
+        &mut self,
+        document: &Document,
+    ) -> Result<(), Box<dyn Error>> {
+        let card = document
+            .nth(0)
+            .and_then(|root| first_match(&root, wprm::CARD))
+            .ok_or_else(|| {
+                ScrapeError::new(
+                    ErrorCode::SelectorMissing,
+                    format!("No WP Recipe Maker card found for: {}", self.url),
+                )
+            })?
+            .node;
+
+        let name = first_match(&card, wprm::NAME).ok_or_else(|| {
+            ScrapeError::new(
+                ErrorCode::SelectorMissing,
+                format!("No recipe name found for: {}", self.url),
+            )
+        })?;
+        self.name = normalize_text(&name.node.text());
+        self.trace
+            .record_match("name", &name, Some(&self.name), None);
+
+        if let Some(m) = first_match(&card, wprm::SUMMARY) {
+            self.description = Some(normalize_text(&m.node.text()));
+            self.trace
+                .record_match("description", &m, self.description.as_deref(), None);
+        }
+
+        for (key, field) in [
+            ("prep_time", "prep_time"),
+            ("cook_time", "cook_time"),
+            ("total_time", "total_time"),
+        ] {
+            let Some(minutes) = duration(&card, key) else {
+                continue;
+            };
+            match field {
+                "prep_time" => self.prep_time = minutes,
+                "cook_time" => self.cook_time = minutes,
+                _ => self.total_time = minutes,
+            }
+            self.trace.record(
+                field,
+                &format!(".wprm-recipe-{}-hours, .wprm-recipe-{}-minutes", key, key),
+                None,
+                Some(format!("{} min", minutes)),
+            );
+        }
+
+        if let Some(m) = first_match(&card, wprm::INGREDIENTS) {
+            self.trace.record_match("ingredients", &m, None, None);
+            self.parse_wprm_ingredients(&m.node)?;
+        }
+
+        if let Some(m) = first_match(&card, wprm::INSTRUCTIONS) {
+            self.parse_wprm_instructions(&m.node);
+            self.trace.record_match(
+                "instructions",
+                &m,
+                None,
+                Some(format!(
+                    "{} sections, {} steps",
+                    self.instructions.len(),
+                    self.instructions
+                        .iter()
+                        .map(|i| i.steps.len())
+                        .sum::<usize>()
+                )),
+            );
+        }
+
+        if let Some(m) = first_match(&card, wprm::SERVINGS) {
+            let raw = m.node.text();
+            if let Some(servings) = leading_number(&raw) {
+                self.servings = servings.into();
+                self.trace.record_match("servings", &m, Some(&raw), None);
+            }
+        }
+
+        if let Some(m) = first_match(&card, wprm::CUISINE) {
+            self.cuisine = normalize_text(&m.node.text());
+            self.trace
+                .record_match("cuisine", &m, Some(&self.cuisine), None);
+        }
+
+        if let Some(m) = first_match(&card, wprm::COURSE) {
+            self.category = normalize_text(&m.node.text());
+            self.trace
+                .record_match("category", &m, Some(&self.category), None);
+        }
+
+        if let Some((m, src)) = first_match(&card, wprm::VIDEO).and_then(|m| {
+            let src = m.node.find(Name("iframe")).next()?.attr("src")?.to_string();
+            Some((m, src))
+        }) {
+            self.video = Some(src);
+            self.trace
+                .record_match("video", &m, self.video.as_deref(), None);
+        }
+
+        if let Some(m) = first_match(&card, wprm::NOTES) {
+            let raw_notes = Self::notes_text(&m.node).trim().to_string();
+            self.notes = Some(Self::clean_notes(&raw_notes));
+            self.trace.record_match(
+                "notes",
+                &m,
+                Some(&raw_notes),
+                Some("clean_notes".to_string()),
+            );
+        }
+
+        if let Some(m) = first_match(&card, wprm::NUTRITION) {
+            self.macros = Macros::from_text(&m.node.text());
+            self.trace.record_match(
+                "macros",
+                &m,
+                None,
+                self.macros
+                    .as_ref()
+                    .map(|_| "Macros::from_text".to_string()),
+            );
+        }
+
+        Ok(())
+    }
+
+    /// Parses the `wprm-recipe-ingredient` items of the ingredients container.
+    ///
+    /// # Errors
+    ///
+    /// Returns `E_SELECTOR_MISSING` if an ingredient has no name span.
+    fn parse_wprm_ingredients(&mut self, list: &Node) -> Result<(), Box<dyn Error>> {
+        let mut ingredients = Vec::new();
+
+        for ingredient in list.find(Class("wprm-recipe-ingredient")) {
+            let span = |class: &str| {
+                ingredient
+                    .find(Class(class))
+                    .next()
+                    .map(|n| normalize_text(&n.text()))
+                    .filter(|t| !t.is_empty())
+            };
+
+            let name = span("wprm-recipe-ingredient-name").ok_or_else(|| {
+                ScrapeError::new(
+                    ErrorCode::SelectorMissing,
+                    format!(
+                        "Error building ingredients for: {}. No ingredient name found:{} ",
+                        self.url,
+                        ingredient.text()
+                    ),
+                )
+            })?;
+
+            let raw = ingredient.text().trim().to_string();
+            let (brand, name) = brand::split_brand(&name);
+            let (optional, to_taste) = Ingredient::flags(&normalize_text(&raw));
+
+            ingredients.push(Ingredient {
+                name,
+                brand,
+                quantity: span("wprm-recipe-ingredient-amount")
+                    .and_then(|a| amount(&a))
+                    .unwrap_or(0.0),
+                units: span("wprm-recipe-ingredient-unit").and_then(|u| Unit::from(&u).ok()),
+                prepped: span("wprm-recipe-ingredient-notes"),
+                optional,
+                to_taste,
+                sub_recipe: sub_recipe::link(&ingredient, &self.url),
+                raw,
+            });
+        }
+
+        self.add_ingredients(ingredients);
+
+        Ok(())
+    }
+
+    /// Parses the instruction groups of the instructions container, one section per
+    /// `wprm-recipe-instruction-group` named by its group heading. A container with no
+    /// groups is read as a single unnamed section.
+    fn parse_wprm_instructions(&mut self, list: &Node) {
+        let groups: Vec<Node> = list.find(Class("wprm-recipe-instruction-group")).collect();
+        let groups = if groups.is_empty() {
+            vec![*list]
+        } else {
+            groups
+        };
+
+        self.instructions = groups
+            .iter()
+            .map(|group| Instruction {
+                section: group
+                    .find(Class("wprm-recipe-group-name"))
+                    .next()
+                    .map(|n| normalize_text(&n.text()))
+                    .filter(|s| !s.is_empty()),
+                steps: group
+                    .find(Class("wprm-recipe-instruction-text"))
+                    .map(|n| normalize_text(&n.text()))
+                    .filter(|s| !s.is_empty())
+                    .collect(),
+                anchors: Vec::new(),
+            })
+            .filter(|i| !i.steps.is_empty())
+            .collect();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CARD: &str = r#"<html><body>
+        <div class="wprm-recipe-container"><div class="wprm-recipe">
+            <h2 class="wprm-recipe-name">Weeknight Chili</h2>
+            <div class="wprm-recipe-summary">A quick chili.</div>
+            <span class="wprm-recipe-prep_time-minutes">15<span class="sr-only"> minutes</span></span>
+            <span class="wprm-recipe-cook_time-hours">1<span class="sr-only"> hour</span></span>
+            <span class="wprm-recipe-cook_time-minutes">30<span class="sr-only"> minutes</span></span>
+            <span class="wprm-recipe-servings">6</span>
+            <span class="wprm-recipe-course">Main Course</span>
+            <div class="wprm-recipe-ingredients-container">
+                <div class="wprm-recipe-ingredient-group"><ul class="wprm-recipe-ingredients">
+                    <li class="wprm-recipe-ingredient"><span class="wprm-recipe-ingredient-amount">1 ½</span> <span class="wprm-recipe-ingredient-unit">pounds</span> <span class="wprm-recipe-ingredient-name">ground beef</span></li>
+                    <li class="wprm-recipe-ingredient"><span class="wprm-recipe-ingredient-amount">2</span> <span class="wprm-recipe-ingredient-unit">tablespoons</span> <span class="wprm-recipe-ingredient-name">chili powder</span> <span class="wprm-recipe-ingredient-notes">divided</span></li>
+                    <li class="wprm-recipe-ingredient"><span class="wprm-recipe-ingredient-name">salt</span> <span class="wprm-recipe-ingredient-notes">to taste</span></li>
+                </ul></div>
+            </div>
+            <div class="wprm-recipe-instructions-container">
+                <div class="wprm-recipe-instruction-group">
+                    <ul class="wprm-recipe-instructions">
+                        <li class="wprm-recipe-instruction"><div class="wprm-recipe-instruction-text">Brown the beef.</div></li>
+                    </ul>
+                </div>
+                <div class="wprm-recipe-instruction-group">
+                    <h4 class="wprm-recipe-group-name">To finish</h4>
+                    <ul class="wprm-recipe-instructions">
+                        <li class="wprm-recipe-instruction"><div class="wprm-recipe-instruction-text">Add the chili powder.</div></li>
+                        <li class="wprm-recipe-instruction"><div class="wprm-recipe-instruction-text">Simmer 30 minutes.</div></li>
+                    </ul>
+                </div>
+            </div>
+            <div class="wprm-nutrition-label-container">Calories: 420kcal | Carbohydrates: 12g | Protein: 30g | Fat: 25g</div>
+        </div></div>
+    </body></html>"#;
+
+    #[test]
+    fn test_parse_wprm_card() {
+        let document = Document::from(CARD);
+        assert!(WprmScraper.detects(&document));
+
+        let mut r = Recipe::default();
+        r.parse_wprm_card(&document)
+            .expect("Failed to parse WPRM card");
+
+        assert_eq!(r.name, "Weeknight Chili");
+        assert_eq!((r.prep_time, r.cook_time, r.total_time), (15, 90, 0));
+        assert_eq!(r.servings, 6);
+        assert_eq!(r.category, "Main Course");
+
+        assert_eq!(r.ingredients.len(), 3);
+        assert_eq!(r.ingredients[0].quantity, 1.5);
+        assert!(matches!(r.ingredients[0].units, Some(Unit::LB)));
+        assert_eq!(r.ingredients[1].prepped.as_deref(), Some("divided"));
+        assert_eq!(r.ingredients[2].quantity, 0.0);
+        assert!(r.ingredients[2].to_taste);
+
+        assert_eq!(r.instructions.len(), 2);
+        assert_eq!(r.instructions[0].section, None);
+        assert_eq!(r.instructions[1].section.as_deref(), Some("To finish"));
+        assert_eq!(r.instructions[1].steps.len(), 2);
+
+        assert!(r.macros.is_some());
+    }
+
+    #[test]
+    fn test_amount() {
+        assert_eq!(amount("2"), Some(2.0));
+        assert_eq!(amount("1 1/2"), Some(1.5));
+        assert_eq!(amount("¾"), Some(0.75));
+        assert_eq!(amount("1-2"), Some(1.0));
+        assert_eq!(amount("a pinch"), None);
+    }
+}
@@ -0,0 +1,144 @@
+use select::node::Node;
+use select::predicate::{Class, Name};
+
+/// A single candidate selector.
+#[derive(Debug, Clone, Copy)]
+pub enum Sel {
+    /// Matches elements with the given class.
+    Class(&'static str),
+    /// Matches elements with the given tag name.
+    Tag(&'static str),
+}
+
+impl Sel {
+    /// The selector in CSS notation, for traces.
+    pub fn css(&self) -> String {
+        match self {
+            Sel::Class(c) => format!(".{}", c),
+            Sel::Tag(t) => t.to_string(),
+        }
+    }
+
+    fn find<'a>(&self, node: &Node<'a>) -> Option<Node<'a>> {
+        match self {
+            Sel::Class(c) => node.find(Class(*c)).next(),
+            Sel::Tag(t) => node.find(Name(*t)).next(),
+        }
+    }
+}
+
+/// An ordered list of candidate selectors for one field, each with the confidence
+/// that a match is really that field. The first candidate is the plugin's current
+/// markup; later ones cover older or drifted markup.
+pub type Chain = &'static [(Sel, f32)];
+
+/// A node found by one of a chain's candidates.
+pub struct Match<'a> {
+    pub node: Node<'a>,
+    pub selector: String,
+    pub confidence: f32,
+}
+
+/// Finds the first candidate in `chain` that matches inside `node`.
+pub fn first_match<'a>(node: &Node<'a>, chain: Chain) -> Option<Match<'a>> {
+    chain.iter().find_map(|(sel, confidence)| {
+        sel.find(node).map(|found| Match {
+            node: found,
+            selector: sel.css(),
+            confidence: *confidence,
+        })
+    })
+}
+
+/// Selector chains for the Tasty Recipes card.
+pub mod tasty {
+    use super::{Chain, Sel};
+
+    pub const NAME: Chain = &[
+        (Sel::Class("tasty-recipes-title"), 1.0),
+        (Sel::Tag("h2"), 0.9),
+        (Sel::Tag("h3"), 0.5),
+    ];
+    pub const TOTAL_TIME: Chain = &[
+        (Sel::Class("tasty-recipes-total-time"), 1.0),
+        (Sel::Class("total-time"), 0.7),
+    ];
+    pub const DESCRIPTION: Chain = &[
+        (Sel::Class("tasty-recipes-description-body"), 1.0),
+        (Sel::Class("tasty-recipes-description"), 0.8),
+    ];
+    pub const INGREDIENTS: Chain = &[
+        (Sel::Class("tasty-recipes-ingredients"), 1.0),
+        (Sel::Class("tasty-recipes-ingredients-body"), 0.9),
+    ];
+    pub const INSTRUCTIONS: Chain = &[
+        (Sel::Class("tasty-recipes-instructions"), 1.0),
+        (Sel::Class("tasty-recipes-instructions-body"), 0.9),
+    ];
+    pub const NOTES: Chain = &[
+        (Sel::Class("tasty-recipes-notes"), 1.0),
+        (Sel::Class("tasty-recipes-notes-body"), 0.9),
+    ];
+    pub const NUTRITION: Chain = &[
+        (Sel::Class("tasty-recipes-nutrition"), 1.0),
+        (Sel::Class("tasty-recipes-nutrition-body"), 0.9),
+    ];
+    pub const DETAILS: Chain = &[
+        (Sel::Class("tasty-recipes-other-details"), 1.0),
+        (Sel::Class("tasty-recipes-details"), 0.8),
+    ];
+}
+
+/// Selector chains for the WP Recipe Maker card.
+pub mod wprm {
+    use super::{Chain, Sel};
+
+    pub const CARD: Chain = &[
+        (Sel::Class("wprm-recipe-container"), 1.0),
+        (Sel::Class("wprm-recipe"), 0.9),
+    ];
+    pub const NAME: Chain = &[(Sel::Class("wprm-recipe-name"), 1.0), (Sel::Tag("h2"), 0.6)];
+    pub const SUMMARY: Chain = &[(Sel::Class("wprm-recipe-summary"), 1.0)];
+    pub const INGREDIENTS: Chain = &[
+        (Sel::Class("wprm-recipe-ingredients-container"), 1.0),
+        (Sel::Class("wprm-recipe-ingredients"), 0.9),
+    ];
+    pub const INSTRUCTIONS: Chain = &[
+        (Sel::Class("wprm-recipe-instructions-container"), 1.0),
+        (Sel::Class("wprm-recipe-instructions"), 0.9),
+    ];
+    pub const SERVINGS: Chain = &[(Sel::Class("wprm-recipe-servings"), 1.0)];
+    pub const CUISINE: Chain = &[(Sel::Class("wprm-recipe-cuisine"), 1.0)];
+    pub const COURSE: Chain = &[(Sel::Class("wprm-recipe-course"), 1.0)];
+    pub const NOTES: Chain = &[
+        (Sel::Class("wprm-recipe-notes"), 1.0),
+        (Sel::Class("wprm-recipe-notes-container"), 0.9),
+    ];
+    pub const NUTRITION: Chain = &[
+        (Sel::Class("wprm-nutrition-label-container"), 1.0),
+        (Sel::Class("wprm-nutrition-label"), 0.8),
+    ];
+    pub const VIDEO: Chain = &[
+        (Sel::Class("wprm-recipe-video"), 1.0),
+        (Sel::Class("wprm-recipe-video-container"), 0.9),
+    ];
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use select::document::Document;
+
+    #[test]
+    fn test_first_match_falls_back() {
+        let document = Document::from(r#"<div><header><h2>Easy Meat Lasagna</h2></header></div>"#);
+        let root = document.nth(0).unwrap();
+
+        let found = first_match(&root, tasty::NAME).expect("Expected a match");
+
+        assert_eq!(found.node.text(), "Easy Meat Lasagna");
+        assert_eq!(found.selector, "h2");
+        assert_eq!(found.confidence, 0.9);
+        assert!(first_match(&root, tasty::NOTES).is_none());
+    }
+}
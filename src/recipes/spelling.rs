@@ -0,0 +1,67 @@
+/// Edit distance between two strings, counted in characters.
+pub fn levenshtein(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut prev: Vec<usize> = (0..=b.len()).collect();
+
+    for (i, ca) in a.chars().enumerate() {
+        let mut row = vec![i + 1];
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { 0 } else { 1 };
+            row.push((prev[j] + cost).min(prev[j + 1] + 1).min(row[j] + 1));
+        }
+        prev = row;
+    }
+
+    prev[b.len()]
+}
+
+/// The largest edit distance accepted as a typo for a term of this length: none for
+/// very short terms, one for up to five characters and two beyond that.
+fn max_distance(term: &str) -> usize {
+    match term.chars().count() {
+        0..=3 => 0,
+        4..=5 => 1,
+        _ => 2,
+    }
+}
+
+/// Suggests the closest word in `vocabulary` for a misspelled search term
+/// ("brocoli" → "broccoli"), for use when a query returns no hits.
+///
+/// # Returns
+///
+/// - `None` if the term is already in the vocabulary or nothing is close enough.
+pub fn suggest<'a>(term: &str, vocabulary: impl IntoIterator<Item = &'a str>) -> Option<&'a str> {
+    let term = term.trim().to_lowercase();
+    let limit = max_distance(&term);
+
+    let mut best: Option<(usize, &str)> = None;
+    for word in vocabulary {
+        let distance = levenshtein(&term, &word.to_lowercase());
+        if distance == 0 {
+            return None;
+        }
+        if distance <= limit && best.is_none_or(|(d, _)| distance < d) {
+            best = Some((distance, word));
+        }
+    }
+
+    best.map(|(_, word)| word)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_suggest() {
+        let vocabulary = ["broccoli", "basil", "garlic", "egg"];
+
+        assert_eq!(levenshtein("brocoli", "broccoli"), 1);
+        assert_eq!(suggest("brocoli", vocabulary), Some("broccoli"));
+        assert_eq!(suggest("Garlik", vocabulary), Some("garlic"));
+        assert_eq!(suggest("basil", vocabulary), None);
+        assert_eq!(suggest("eg", vocabulary), None);
+        assert_eq!(suggest("chicken", vocabulary), None);
+    }
+}
@@ -0,0 +1,137 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Ingredient, Unit};
+
+/// Chili-related ingredients and their relative heat per teaspoon or per pepper.
+/// Hottest first, so an ingredient naming two of them ("habanero hot sauce") counts as
+/// the hotter one.
+const HEAT: &[(&str, f32)] = &[
+    ("ghost pepper", 6.0),
+    ("scotch bonnet", 4.0),
+    ("habanero", 4.0),
+    ("bird's eye", 3.0),
+    ("thai chili", 3.0),
+    ("thai chile", 3.0),
+    ("cayenne", 3.0),
+    ("chili crisp", 2.0),
+    ("chili oil", 2.0),
+    ("chipotle", 2.0),
+    ("gochujang", 2.0),
+    ("harissa", 2.0),
+    ("sambal", 2.0),
+    ("serrano", 2.0),
+    ("sriracha", 2.0),
+    ("crushed red pepper", 1.5),
+    ("red pepper flakes", 1.5),
+    ("chili flakes", 1.5),
+    ("gochugaru", 1.0),
+    ("hot sauce", 1.0),
+    ("jalapeño", 1.0),
+    ("jalapeno", 1.0),
+    ("chili powder", 0.5),
+    ("chile powder", 0.5),
+    ("poblano", 0.5),
+];
+
+/// Estimated heat of a recipe.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum SpiceLevel {
+    #[default]
+    None,
+    Mild,
+    Medium,
+    Hot,
+}
+
+impl SpiceLevel {
+    /// Estimates the spice level from the chili-related ingredients and their quantities.
+    ///
+    /// Each matching ingredient contributes its heat multiplied by the amount used,
+    /// converted to teaspoons where a unit is known. Ingredients without a quantity
+    /// count once.
+    ///
+    /// # Arguments
+    /// - `ingredients`: The recipe's parsed ingredients.
+    pub(super) fn estimate(ingredients: &[Ingredient]) -> Self {
+        let score: f32 = ingredients
+            .iter()
+            .filter_map(|ingredient| {
+                let name = ingredient.name.to_lowercase();
+                let heat = HEAT.iter().find(|(k, _)| name.contains(k))?.1;
+
+                let quantity = if ingredient.quantity > 0.0 {
+                    ingredient.quantity
+                } else {
+                    1.0
+                };
+                let amount = match ingredient.units {
+                    Some(Unit::TABLESPOON) => quantity * 3.0,
+                    Some(Unit::CUP) => quantity * 48.0,
+                    _ => quantity,
+                };
+
+                Some(heat * amount)
+            })
+            .sum();
+
+        match score {
+            s if s <= 0.0 => SpiceLevel::None,
+            s if s < 3.0 => SpiceLevel::Mild,
+            s if s < 8.0 => SpiceLevel::Medium,
+            _ => SpiceLevel::Hot,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ingredient(name: &str, quantity: f32, units: Option<Unit>) -> Ingredient {
+        Ingredient {
+            name: name.into(),
+            brand: None,
+            quantity,
+            units,
+            prepped: None,
+            optional: false,
+            to_taste: false,
+            sub_recipe: None,
+            raw: name.into(),
+        }
+    }
+
+    #[test]
+    fn test_estimate() {
+        assert_eq!(
+            SpiceLevel::estimate(&[ingredient("bell pepper", 2.0, None)]),
+            SpiceLevel::None
+        );
+        assert_eq!(
+            SpiceLevel::estimate(&[ingredient("red pepper flakes", 0.5, Some(Unit::TEASPOON))]),
+            SpiceLevel::Mild
+        );
+        assert_eq!(
+            SpiceLevel::estimate(&[ingredient("sriracha", 1.0, Some(Unit::TABLESPOON))]),
+            SpiceLevel::Medium
+        );
+        assert_eq!(
+            SpiceLevel::estimate(&[ingredient("habanero peppers", 3.0, None)]),
+            SpiceLevel::Hot
+        );
+    }
+
+    #[test]
+    fn test_estimate_hottest_name_wins() {
+        assert!(HEAT.windows(2).all(|w| w[0].1 >= w[1].1));
+        assert_eq!(
+            SpiceLevel::estimate(&[ingredient("habanero hot sauce", 1.0, None)]),
+            SpiceLevel::Medium
+        );
+        assert_eq!(
+            SpiceLevel::estimate(&[ingredient("hot sauce", 1.0, None)]),
+            SpiceLevel::Mild
+        );
+    }
+}
@@ -0,0 +1,152 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+/// Structured leftovers guidance pulled out of a recipe's notes.
+///
+/// Durations are stored in days; a range such as "3-4 days" keeps the upper bound.
+///
+/// # Fields
+///
+/// - `fridge_days`: How long the dish keeps refrigerated.
+/// - `freezer_days`: How long the dish keeps frozen.
+/// - `room_temp_days`: How long the dish keeps at room temperature.
+/// - `reheat`: The sentence describing how to reheat, if any.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct StorageInfo {
+    pub fridge_days: Option<u32>,
+    pub freezer_days: Option<u32>,
+    pub room_temp_days: Option<u32>,
+    pub reheat: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum Place {
+    Fridge,
+    Freezer,
+    RoomTemp,
+}
+
+impl StorageInfo {
+    /// Extracts storage and reheating guidance from cleaned recipe notes.
+    ///
+    /// Each sentence is split into clauses. A duration is attributed to the storage
+    /// place mentioned in its own clause, or to the last place mentioned earlier in
+    /// the same sentence ("Refrigerate leftovers, covered, for 3 days").
+    ///
+    /// # Arguments
+    /// - `notes`: The cleaned notes text of a recipe.
+    ///
+    /// # Returns
+    /// - `Some(StorageInfo)` if any guidance was found, otherwise `None`.
+    pub fn from_notes(notes: &str) -> Option<Self> {
+        let duration_re =
+            Regex::new(r"(?i)(\d+)(?:\s*(?:-|–|to)\s*(\d+))?\s*(day|week|month)s?").unwrap();
+        let clause_re = Regex::new(r"(?i),|\bor\b|\band\b").unwrap();
+
+        let mut info = StorageInfo::default();
+
+        for sentence in notes.split(['.', ';', '!']) {
+            let mut place = None;
+
+            for clause in clause_re.split(sentence) {
+                if let Some(p) = Self::place(clause) {
+                    place = Some(p);
+                }
+
+                let (Some(p), Some(caps)) = (place, duration_re.captures(clause)) else {
+                    continue;
+                };
+
+                // A duration too long to count in days is not a storage hint
+                let Some(days) = caps
+                    .get(2)
+                    .or(caps.get(1))
+                    .and_then(|m| m.as_str().parse::<u32>().ok())
+                    .and_then(|amount| match caps[3].to_lowercase().as_str() {
+                        "week" => amount.checked_mul(7),
+                        "month" => amount.checked_mul(30),
+                        _ => Some(amount),
+                    })
+                else {
+                    continue;
+                };
+
+                let slot = match p {
+                    Place::Fridge => &mut info.fridge_days,
+                    Place::Freezer => &mut info.freezer_days,
+                    Place::RoomTemp => &mut info.room_temp_days,
+                };
+                slot.get_or_insert(days);
+            }
+
+            if info.reheat.is_none() && sentence.to_lowercase().contains("reheat") {
+                info.reheat = Some(sentence.trim().to_string());
+            }
+        }
+
+        if info == StorageInfo::default() {
+            None
+        } else {
+            Some(info)
+        }
+    }
+
+    fn place(clause: &str) -> Option<Place> {
+        let clause = clause.to_lowercase();
+
+        if clause.contains("freez") {
+            Some(Place::Freezer)
+        } else if clause.contains("fridge") || clause.contains("refrigerat") {
+            Some(Place::Fridge)
+        } else if clause.contains("room temperature") || clause.contains("counter") {
+            Some(Place::RoomTemp)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_notes() {
+        let notes = "Leftovers keep 4 days refrigerated, or freeze up to 3 months. \
+                     Reheat gently on the stovetop with a splash of water.";
+
+        let info = StorageInfo::from_notes(notes).expect("Expected storage info");
+
+        assert_eq!(info.fridge_days, Some(4));
+        assert_eq!(info.freezer_days, Some(90));
+        assert_eq!(info.room_temp_days, None);
+        assert_eq!(
+            info.reheat.as_deref(),
+            Some("Reheat gently on the stovetop with a splash of water")
+        );
+    }
+
+    #[test]
+    fn test_from_notes_carries_place() {
+        let info = StorageInfo::from_notes("Refrigerate leftovers, covered, for 3-4 days.")
+            .expect("Expected storage info");
+
+        assert_eq!(info.fridge_days, Some(4));
+        assert_eq!(StorageInfo::from_notes("Use fresh basil if you can."), None);
+    }
+
+    #[test]
+    fn test_from_notes_overflow() {
+        assert_eq!(
+            StorageInfo::from_notes("Keeps in the freezer for 200000000 months."),
+            None
+        );
+
+        let info = StorageInfo::from_notes(
+            "Keeps in the freezer for 200000000 months. Refrigerate for 2 weeks.",
+        )
+        .expect("Expected storage info");
+        assert_eq!(info.freezer_days, None);
+        assert_eq!(info.fridge_days, Some(14));
+    }
+}
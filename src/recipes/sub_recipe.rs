@@ -0,0 +1,64 @@
+use reqwest::Url;
+use select::node::Node;
+use select::predicate::Name;
+
+/// Words in an ingredient line that mark it as a whole other recipe
+/// ("1 batch pizza dough", "1 recipe marinara sauce").
+const MARKERS: &[&str] = &["recipe", "batch", "homemade"];
+
+/// Finds the sub-recipe an ingredient line links to, if any.
+///
+/// A link counts when it points at the same site as the recipe (affiliate and shop
+/// links go elsewhere) or when the line says it is a recipe or batch of something.
+///
+/// # Arguments
+///
+/// - `line`: The ingredient's `<li>` node.
+/// - `recipe_url`: The URL of the recipe being parsed, used to resolve relative links.
+///
+/// # Returns
+///
+/// - The absolute URL of the linked recipe, or `None` if the line has no such link.
+pub fn link(line: &Node, recipe_url: &str) -> Option<String> {
+    let base = Url::parse(recipe_url).ok()?;
+    let text = line.text().to_lowercase();
+    let marked = MARKERS.iter().any(|m| text.contains(m));
+
+    line.find(Name("a"))
+        .filter_map(|a| a.attr("href"))
+        .filter_map(|href| base.join(href).ok())
+        .find(|url| {
+            matches!(url.scheme(), "http" | "https")
+                && (marked || url.host_str() == base.host_str())
+                && url.path() != base.path()
+        })
+        .map(|url| url.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use select::document::Document;
+
+    #[test]
+    fn test_link() {
+        let base = "https://www.aheadofthyme.com/homemade-pizza/";
+        let doc = Document::from(r#"<li>1 batch <a href="/pizza-dough/">pizza dough</a></li>"#);
+        assert_eq!(
+            link(&doc.nth(0).unwrap(), base).as_deref(),
+            Some("https://www.aheadofthyme.com/pizza-dough/")
+        );
+
+        let doc = Document::from(
+            r#"<li>1 recipe <a href="https://example.com/marinara">marinara sauce</a></li>"#,
+        );
+        assert_eq!(
+            link(&doc.nth(0).unwrap(), base).as_deref(),
+            Some("https://example.com/marinara")
+        );
+
+        let doc =
+            Document::from(r#"<li>1 cup <a href="https://amazon.com/x">pizza sauce</a></li>"#);
+        assert_eq!(link(&doc.nth(0).unwrap(), base), None);
+    }
+}
@@ -0,0 +1,81 @@
+use regex::Regex;
+
+/// Regional or alternate ingredient names, each mapped to the canonical name recipes
+/// and pantry items are compared by. Multi-word names are matched before single words.
+pub const SYNONYMS: &[(&str, &str)] = &[
+    ("confectioners' sugar", "powdered sugar"),
+    ("garbanzo bean", "chickpea"),
+    ("spring onion", "green onion"),
+    ("double cream", "heavy cream"),
+    ("icing sugar", "powdered sugar"),
+    ("minced beef", "ground beef"),
+    ("bicarbonate of soda", "baking soda"),
+    ("coriander leaves", "cilantro"),
+    ("fresh coriander", "cilantro"),
+    ("courgette", "zucchini"),
+    ("aubergine", "eggplant"),
+    ("scallion", "green onion"),
+    ("capsicum", "bell pepper"),
+    ("rocket", "arugula"),
+    ("prawn", "shrimp"),
+];
+
+/// Rewrites every known synonym in `name` to its canonical form, lowercasing and
+/// collapsing whitespace. Plurals are kept ("scallions" → "green onions").
+///
+/// # Examples
+///
+/// ```
+/// use data_collection::recipes::synonyms::normalize;
+///
+/// assert_eq!(normalize("2 Courgettes, sliced"), "2 zucchinis, sliced");
+/// ```
+pub fn normalize(name: &str) -> String {
+    let mut out = name
+        .to_lowercase()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+
+    for (from, to) in SYNONYMS {
+        let re = Regex::new(&format!(r"\b{}(s?)\b", regex::escape(from))).unwrap();
+        out = re.replace_all(&out, format!("{}$1", to)).into_owned();
+    }
+
+    out
+}
+
+/// Expands a search term into its canonical form and every synonym of it, so a query
+/// for "coriander leaves" also matches "cilantro" and "fresh coriander".
+pub fn expand(term: &str) -> Vec<String> {
+    let canonical = normalize(term);
+    let mut terms = vec![canonical.clone()];
+
+    for (from, to) in SYNONYMS {
+        if *to == canonical && !terms.iter().any(|t| t == from) {
+            terms.push(from.to_string());
+        }
+    }
+
+    terms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_and_expand() {
+        assert_eq!(
+            normalize("Scallions, thinly sliced"),
+            "green onions, thinly sliced"
+        );
+        assert_eq!(normalize("fresh  coriander"), "cilantro");
+        assert_eq!(normalize("rocketship"), "rocketship");
+
+        let terms = expand("Spring Onion");
+        assert_eq!(terms[0], "green onion");
+        assert!(terms.contains(&"scallion".to_string()));
+        assert!(terms.contains(&"spring onion".to_string()));
+    }
+}
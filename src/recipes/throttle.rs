@@ -0,0 +1,233 @@
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use std::collections::hash_map::RandomState;
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+/// How fast requests to any one host may be sent.
+///
+/// # Fields
+///
+/// - `rps`: Requests per second per host. Zero or less disables the limit.
+/// - `jitter`: Up to this much extra delay is added at random to each request, so a
+///   crawl doesn't hit the host on a fixed beat.
+///
+/// Neither the interval nor the jitter is ever longer than `MAX_WAIT`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RateLimit {
+    pub rps: f64,
+    pub jitter: Duration,
+}
+
+impl Default for RateLimit {
+    fn default() -> Self {
+        RateLimit {
+            rps: 1.0,
+            jitter: Duration::from_millis(500),
+        }
+    }
+}
+
+/// The longest a rate limit may hold a request back, so a tiny `rps` or a huge jitter
+/// can't push a slot past what an `Instant` holds.
+pub const MAX_WAIT: Duration = Duration::from_secs(24 * 60 * 60);
+
+impl RateLimit {
+    /// The minimum time between two requests to the same host.
+    fn interval(&self) -> Duration {
+        if self.rps > 0.0 {
+            Duration::try_from_secs_f64(1.0 / self.rps)
+                .unwrap_or(MAX_WAIT)
+                .min(MAX_WAIT)
+        } else {
+            Duration::ZERO
+        }
+    }
+}
+
+/// Used when a 429 carries no Retry-After we can read (e.g. an HTTP date).
+const DEFAULT_PAUSE: Duration = Duration::from_secs(60);
+
+/// Never honour a Retry-After longer than this, so one host can't stall a crawl for hours.
+const MAX_PAUSE: Duration = Duration::from_secs(15 * 60);
+
+/// The per-host request schedule of one fetcher: when each host may next be sent a
+/// request under its `RateLimit`, and which hosts asked us to back off.
+///
+/// # Fields
+///
+/// - `rate_limit`: How fast requests to any one host may be sent.
+/// - `slots`: When each host may next be sent a request.
+/// - `pauses`: Hosts that asked us to back off, and until when.
+#[derive(Debug, Default)]
+pub struct Throttle {
+    rate_limit: RateLimit,
+    slots: Mutex<HashMap<String, Instant>>,
+    pauses: Mutex<HashMap<String, Instant>>,
+}
+
+/// A random delay up to `max`.
+fn jitter(max: Duration) -> Duration {
+    if max.is_zero() {
+        return Duration::ZERO;
+    }
+    let max = max.min(MAX_WAIT);
+    // Every `RandomState` is keyed differently, which is random enough for spacing requests
+    let sample = RandomState::new().build_hasher().finish();
+    max.mul_f64((sample % 1000) as f64 / 1000.0)
+}
+
+/// Books the next request to `host` at the earliest time `rate_limit` allows from `now`,
+/// plus `jitter`, and holds the following slot one interval after it.
+fn reserve(
+    slots: &mut HashMap<String, Instant>,
+    host: &str,
+    rate_limit: &RateLimit,
+    now: Instant,
+    jitter: Duration,
+) -> Instant {
+    let slot = slots.get(host).copied().unwrap_or(now).max(now) + jitter;
+    slots.insert(host.to_string(), slot + rate_limit.interval());
+    slot
+}
+
+/// Reads how long to back off from a response's Retry-After header.
+pub fn retry_after(headers: &HeaderMap) -> Duration {
+    headers
+        .get(RETRY_AFTER)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.trim().parse::<u64>().ok())
+        .map_or(DEFAULT_PAUSE, Duration::from_secs)
+        .min(MAX_PAUSE)
+}
+
+impl Throttle {
+    pub fn new(rate_limit: RateLimit) -> Self {
+        Throttle {
+            rate_limit,
+            ..Default::default()
+        }
+    }
+
+    /// Pauses every request to `host` for `duration`, extending any pause already in place.
+    pub fn pause(&self, host: &str, duration: Duration) {
+        let until = Instant::now() + duration;
+        let mut pauses = self.pauses.lock().unwrap();
+        let entry = pauses.entry(host.to_string()).or_insert(until);
+        *entry = (*entry).max(until);
+    }
+
+    /// Waits out any pause on `host`, then for the host's next slot under the rate limit,
+    /// before a request is sent.
+    pub async fn wait(&self, host: &str) {
+        let until = self.pauses.lock().unwrap().get(host).copied();
+
+        if let Some(until) = until {
+            let now = Instant::now();
+            if until > now {
+                tokio::time::sleep(until - now).await;
+            }
+        }
+
+        let now = Instant::now();
+        let slot = reserve(
+            &mut self.slots.lock().unwrap(),
+            host,
+            &self.rate_limit,
+            now,
+            jitter(self.rate_limit.jitter),
+        );
+        if slot > now {
+            tokio::time::sleep(slot - now).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(retry_after(&headers), DEFAULT_PAUSE);
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("120"));
+        assert_eq!(retry_after(&headers), Duration::from_secs(120));
+
+        headers.insert(RETRY_AFTER, HeaderValue::from_static("86400"));
+        assert_eq!(retry_after(&headers), MAX_PAUSE);
+    }
+
+    #[test]
+    fn test_reserve() {
+        let rate_limit = RateLimit {
+            rps: 2.0,
+            jitter: Duration::ZERO,
+        };
+        let mut slots = HashMap::new();
+        let now = Instant::now();
+        let half = Duration::from_millis(500);
+
+        assert_eq!(
+            reserve(&mut slots, "a.com", &rate_limit, now, Duration::ZERO),
+            now
+        );
+        assert_eq!(
+            reserve(&mut slots, "a.com", &rate_limit, now, Duration::ZERO),
+            now + half
+        );
+        assert_eq!(
+            reserve(&mut slots, "b.com", &rate_limit, now, Duration::ZERO),
+            now
+        );
+        assert_eq!(
+            reserve(&mut slots, "a.com", &rate_limit, now, half),
+            now + 2 * half + half
+        );
+
+        // A host that has been idle longer than the interval goes straight away
+        let later = now + Duration::from_secs(10);
+        assert_eq!(
+            reserve(&mut slots, "a.com", &rate_limit, later, Duration::ZERO),
+            later
+        );
+
+        let unlimited = RateLimit {
+            rps: 0.0,
+            jitter: Duration::ZERO,
+        };
+        assert_eq!(unlimited.interval(), Duration::ZERO);
+        assert!(jitter(half) < half);
+
+        // Limits too slow to schedule are held to `MAX_WAIT` rather than overflowing
+        let glacial = RateLimit {
+            rps: 1e-300,
+            jitter: Duration::MAX,
+        };
+        assert_eq!(glacial.interval(), MAX_WAIT);
+        assert!(jitter(glacial.jitter) < MAX_WAIT);
+        reserve(&mut slots, "c.com", &glacial, now, jitter(glacial.jitter));
+    }
+
+    #[tokio::test]
+    async fn test_pause() {
+        let unlimited = RateLimit {
+            rps: 0.0,
+            jitter: Duration::ZERO,
+        };
+        let throttle = Throttle::new(unlimited);
+        let pause = Duration::from_millis(50);
+        throttle.pause("a.com", pause);
+
+        let start = Instant::now();
+        throttle.wait("b.com").await;
+        Throttle::new(unlimited).wait("a.com").await;
+        assert!(start.elapsed() < pause);
+
+        throttle.wait("a.com").await;
+        assert!(start.elapsed() >= pause);
+    }
+}
@@ -0,0 +1,82 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use super::selectors::Match;
+
+/// How a single recipe field was extracted.
+///
+/// # Fields
+///
+/// - `field`: The `Recipe` field that was set.
+/// - `selector`: The selector (or source) the value was read from.
+/// - `raw`: The raw text captured, before any transform.
+/// - `transform`: The transform applied to the raw text and its result, if any.
+/// - `confidence`: The confidence of the selector chain candidate that matched, if one did.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct FieldTrace {
+    pub field: String,
+    pub selector: String,
+    pub raw: Option<String>,
+    pub transform: Option<String>,
+    pub confidence: Option<f32>,
+}
+
+/// A record of how every field of a recipe was parsed, for debugging new sites.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct ParseTrace {
+    pub url: String,
+    pub fields: Vec<FieldTrace>,
+}
+
+impl ParseTrace {
+    /// Records that `field` was read from `selector`.
+    pub fn record(
+        &mut self,
+        field: &str,
+        selector: &str,
+        raw: Option<&str>,
+        transform: Option<String>,
+    ) {
+        self.fields.push(FieldTrace {
+            field: field.into(),
+            selector: selector.into(),
+            raw: raw.map(|r| r.trim().to_string()),
+            transform,
+            confidence: None,
+        });
+    }
+
+    /// Records that `field` was read from a selector chain match.
+    pub fn record_match(
+        &mut self,
+        field: &str,
+        found: &Match,
+        raw: Option<&str>,
+        transform: Option<String>,
+    ) {
+        self.record(field, &found.selector, raw, transform);
+        if let Some(last) = self.fields.last_mut() {
+            last.confidence = Some(found.confidence);
+        }
+    }
+
+    /// The lowest confidence of any field parsed through a selector chain.
+    pub fn confidence(&self) -> Option<f32> {
+        self.fields
+            .iter()
+            .filter_map(|f| f.confidence)
+            .reduce(f32::min)
+    }
+
+    /// Writes the trace as pretty JSON to `<dir>/<id>.json`, creating `dir` if needed.
+    pub fn write_to(&self, dir: &Path, id: &str) -> Result<(), Box<dyn Error>> {
+        fs::create_dir_all(dir)?;
+        fs::write(
+            dir.join(format!("{}.json", id)),
+            serde_json::to_string_pretty(self)?,
+        )?;
+        Ok(())
+    }
+}
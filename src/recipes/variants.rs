@@ -0,0 +1,237 @@
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+use super::Instruction;
+
+/// An appliance an alternate set of instructions is written for.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum Appliance {
+    InstantPot,
+    SlowCooker,
+    AirFryer,
+}
+
+impl Appliance {
+    /// Recognises an appliance named in a section header or note label.
+    pub fn from(str: &str) -> Option<Self> {
+        let str = str.to_lowercase();
+
+        if str.contains("instant pot") || str.contains("pressure cooker") {
+            Some(Appliance::InstantPot)
+        } else if str.contains("slow cooker") || str.contains("crock") {
+            Some(Appliance::SlowCooker)
+        } else if str.contains("air fryer") || str.contains("air-fryer") {
+            Some(Appliance::AirFryer)
+        } else {
+            None
+        }
+    }
+}
+
+/// Alternate instructions for a specific appliance.
+///
+/// # Fields
+///
+/// - `appliance`: The appliance these steps are written for.
+/// - `steps`: The steps of the variant.
+/// - `cook_time`: The sum of the durations mentioned in the steps, in minutes. A duration
+///   given as an alternative ("4 hours on low or 2 hours on high") is not added.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RecipeVariant {
+    pub appliance: Appliance,
+    pub steps: Vec<String>,
+    pub cook_time: Option<u32>,
+}
+
+impl RecipeVariant {
+    fn new(appliance: Appliance, steps: Vec<String>) -> Self {
+        let cook_time = Self::cook_time(&steps);
+        RecipeVariant {
+            appliance,
+            steps,
+            cook_time,
+        }
+    }
+
+    fn cook_time(steps: &[String]) -> Option<u32> {
+        let re =
+            Regex::new(r"(?i)(\d+)(?:\s*(?:-|–|to)\s*(\d+))?\s*(hour|hr|minute|min)s?\b").unwrap();
+
+        let alternative_re = Regex::new(r"(?i)\bor\s+(?:for\s+|about\s+)?$").unwrap();
+
+        // A total too long to count in minutes is left unknown
+        let mut minutes = 0u32;
+        for (step, caps) in steps
+            .iter()
+            .flat_map(|step| re.captures_iter(step).map(move |caps| (step, caps)))
+        {
+            if alternative_re.is_match(&step[..caps.get(0).unwrap().start()]) {
+                continue;
+            }
+
+            let amount = caps
+                .get(2)
+                .or(caps.get(1))
+                .and_then(|m| m.as_str().parse::<u32>().ok())
+                .unwrap_or(0);
+
+            let step_minutes = match caps[3].to_lowercase().as_str() {
+                "hour" | "hr" => amount.checked_mul(60)?,
+                _ => amount,
+            };
+            minutes = minutes.checked_add(step_minutes)?;
+        }
+
+        (minutes > 0).then_some(minutes)
+    }
+}
+
+/// Pulls appliance-specific variants out of a recipe's instructions and notes.
+///
+/// Instruction sections whose header names an appliance ("Instant Pot Instructions")
+/// are moved out of `instructions`, as long as at least one regular section remains.
+/// Notes labelled with an appliance ("Slow cooker: cook on low for 6 hours.") are
+/// split into sentences and added as well. A label covers the sentences after it up to
+/// the end of the paragraph, or up to the next sentence that opens with a label of its
+/// own ("Storage: ...").
+///
+/// # Arguments
+/// - `instructions`: The parsed instruction sections; appliance sections are removed.
+/// - `notes`: The cleaned notes text, if any.
+///
+/// # Returns
+/// - The variants found, in document order (sections first, then notes).
+pub fn extract(instructions: &mut Vec<Instruction>, notes: Option<&str>) -> Vec<RecipeVariant> {
+    let mut variants = Vec::new();
+
+    let is_variant = |i: &Instruction| i.section.as_deref().and_then(Appliance::from).is_some();
+    if instructions.iter().any(|i| !is_variant(i)) {
+        let (found, rest): (Vec<_>, Vec<_>) = instructions.drain(..).partition(is_variant);
+        *instructions = rest;
+
+        for instruction in found {
+            if let Some(appliance) = instruction.section.as_deref().and_then(Appliance::from) {
+                variants.push(RecipeVariant::new(appliance, instruction.steps));
+            }
+        }
+    }
+
+    if let Some(notes) = notes {
+        let label_re = Regex::new(
+            r"(?i)(instant pot|pressure cooker|slow cooker|crock[- ]?pot|air[- ]fryer)(?:\s+(?:method|instructions|version|directions))?\s*:",
+        )
+        .unwrap();
+
+        let heading_re = Regex::new(r"^[A-Z][\w'-]*(?:\s+[\w'-]+){0,3}\s*:").unwrap();
+
+        let labels: Vec<_> = label_re.captures_iter(notes).collect();
+        for (n, caps) in labels.iter().enumerate() {
+            let label = caps.get(0).unwrap();
+            let end = labels
+                .get(n + 1)
+                .map_or(notes.len(), |next| next.get(0).unwrap().start());
+            let block = notes[label.end()..end].trim_start();
+            let paragraph = block.split('\n').next().unwrap_or_default();
+
+            let steps: Vec<String> = paragraph
+                .split_inclusive(['.', '!'])
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .take_while(|s| !heading_re.is_match(s))
+                .collect();
+
+            if let (Some(appliance), false) = (Appliance::from(&caps[1]), steps.is_empty()) {
+                variants.push(RecipeVariant::new(appliance, steps));
+            }
+        }
+    }
+
+    variants
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::recipes::Recipe;
+    use select::document::Document;
+    use select::predicate::Class;
+
+    #[test]
+    fn test_extract() {
+        let mut instructions = vec![
+            Instruction {
+                section: Some("Stovetop".into()),
+                steps: vec!["Simmer for 2 hours.".into()],
+                anchors: Vec::new(),
+            },
+            Instruction {
+                section: Some("Instant Pot Instructions".into()),
+                steps: vec!["Pressure cook on high for 25 minutes.".into()],
+                anchors: Vec::new(),
+            },
+        ];
+        let notes =
+            "Great with rice. Slow cooker: Add everything to the pot. Cook on low 6-8 hours.";
+
+        let variants = extract(&mut instructions, Some(notes));
+
+        assert_eq!(instructions.len(), 1);
+        assert_eq!(
+            variants,
+            vec![
+                RecipeVariant {
+                    appliance: Appliance::InstantPot,
+                    steps: vec!["Pressure cook on high for 25 minutes.".into()],
+                    cook_time: Some(25),
+                },
+                RecipeVariant {
+                    appliance: Appliance::SlowCooker,
+                    steps: vec![
+                        "Add everything to the pot.".into(),
+                        "Cook on low 6-8 hours.".into()
+                    ],
+                    cook_time: Some(480),
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_extract_block_ends() {
+        let notes = "Slow cooker: Cook on low 6 hours. Storage: Keeps 4 days in the fridge.";
+        let variants = extract(&mut Vec::new(), Some(notes));
+        assert_eq!(variants[0].steps, vec!["Cook on low 6 hours."]);
+        assert_eq!(variants[0].cook_time, Some(360));
+
+        // Notes as the scrapers store them, one paragraph per line
+        let document = Document::from(
+            "<div class=\"notes\"><p>Air fryer: Cook at 400F for 12 minutes.</p><p>Serve with lemon wedges.</p></div>",
+        );
+        let node = document.find(Class("notes")).next().unwrap();
+        let notes = Recipe::clean_notes(&Recipe::notes_text(&node));
+        let variants = extract(&mut Vec::new(), Some(&notes));
+        assert_eq!(variants[0].steps, vec!["Cook at 400F for 12 minutes."]);
+    }
+
+    #[test]
+    fn test_cook_time_skips_alternatives() {
+        let steps = vec![
+            "Cook on low for 4 hours or 2 hours on high.".to_string(),
+            "Rest 10 minutes.".to_string(),
+        ];
+        assert_eq!(RecipeVariant::cook_time(&steps), Some(250));
+    }
+
+    #[test]
+    fn test_cook_time_overflow() {
+        let steps = vec!["Cook on low for 99999999 hours.".to_string()];
+        assert_eq!(RecipeVariant::cook_time(&steps), None);
+
+        let steps = vec![
+            "Cook for 4000000000 minutes.".to_string(),
+            "Then 4000000000 minutes more.".to_string(),
+        ];
+        assert_eq!(RecipeVariant::cook_time(&steps), None);
+    }
+}
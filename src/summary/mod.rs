@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+
+use crate::error::ErrorCode;
+
+/// Process exit codes, so wrapper scripts and schedulers can branch on the outcome.
+pub mod exit {
+    /// At least one recipe was found, and every recipe found was stored.
+    pub const SUCCESS: u8 = 0;
+    /// Nothing was stored: the listing could not be fetched, had no recipes on it, or
+    /// every recipe failed.
+    pub const TOTAL_FAILURE: u8 = 1;
+    /// Bad arguments or the database could not be reached; nothing was attempted.
+    pub const CONFIG_ERROR: u8 = 2;
+    /// Some recipes were stored and some failed.
+    pub const PARTIAL_FAILURE: u8 = 3;
+}
+
+/// A single recipe that could not be scraped or stored.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq)]
+pub struct Failure {
+    pub url: String,
+    pub code: ErrorCode,
+    pub message: String,
+}
+
+impl Failure {
+    pub fn new(url: &str, error: &(dyn Error + 'static)) -> Self {
+        Failure {
+            url: url.to_string(),
+            code: ErrorCode::of(error),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// The machine-readable result of one run, written by `--summary-out`.
+///
+/// # Fields
+///
+/// - `found`: Recipes found on the listing page, whether or not they parsed.
+/// - `stored`: Recipes written to the database.
+/// - `failures`: Every recipe that failed, with its error code.
+/// - `unpublished`: Pages that became galleries and were handled by `--unpublish-galleries`.
+///   They are not failures.
+/// - `exit_code`: The code the process exits with (see `exit`).
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct RunSummary {
+    pub found: usize,
+    pub stored: usize,
+    pub failures: Vec<Failure>,
+    #[serde(default)]
+    pub unpublished: Vec<String>,
+    pub exit_code: u8,
+}
+
+impl RunSummary {
+    pub fn fail(&mut self, url: &str, error: &(dyn Error + 'static)) {
+        self.failures.push(Failure::new(url, error));
+    }
+
+    /// Works out the exit code from what was stored and what failed, and records it.
+    ///
+    /// A run that found no recipes at all is a total failure, since a listing that
+    /// suddenly has none usually means the site changed.
+    pub fn finish(&mut self) -> u8 {
+        self.exit_code = if self.found == 0 {
+            exit::TOTAL_FAILURE
+        } else if self.failures.is_empty() {
+            exit::SUCCESS
+        } else if self.stored == 0 {
+            exit::TOTAL_FAILURE
+        } else {
+            exit::PARTIAL_FAILURE
+        };
+        self.exit_code
+    }
+
+    /// Writes the summary as pretty JSON to `path`.
+    pub fn write_to(&self, path: &Path) -> Result<(), Box<dyn Error>> {
+        fs::write(path, serde_json::to_string_pretty(self)?)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ScrapeError;
+
+    #[test]
+    fn test_finish() {
+        let mut summary = RunSummary {
+            found: 2,
+            stored: 2,
+            ..Default::default()
+        };
+        assert_eq!(summary.finish(), exit::SUCCESS);
+
+        summary.stored = 1;
+        summary.fail(
+            "https://example.com/lasagna",
+            &ScrapeError::new(ErrorCode::SelectorMissing, "No recipe name found"),
+        );
+        assert_eq!(summary.finish(), exit::PARTIAL_FAILURE);
+        assert_eq!(summary.failures[0].code, ErrorCode::SelectorMissing);
+
+        summary.stored = 0;
+        assert_eq!(summary.finish(), exit::TOTAL_FAILURE);
+
+        let mut empty = RunSummary::default();
+        assert_eq!(empty.finish(), exit::TOTAL_FAILURE);
+
+        // Handled galleries don't fail the run
+        let mut galleries = RunSummary {
+            found: 2,
+            stored: 1,
+            unpublished: vec!["https://example.com/best-lasagna-recipes".to_string()],
+            ..Default::default()
+        };
+        assert_eq!(galleries.finish(), exit::SUCCESS);
+    }
+}
@@ -0,0 +1,125 @@
+use serde::{Deserialize, Serialize};
+use std::error::Error;
+use std::fs;
+use std::path::Path;
+use surrealdb::{engine::remote::ws::Client, Surreal};
+
+use crate::cook_log::{self, CookLogEntry};
+use crate::overlay::{self, RecipeOverlay};
+use crate::pantry::{self, PantryItem};
+
+/// Bumped whenever the layout of `UserData` changes incompatibly.
+pub const FORMAT_VERSION: u32 = 2;
+
+/// Everything the user has entered by hand, as opposed to the scraped corpus which
+/// can always be re-created by crawling again.
+///
+/// # Fields
+///
+/// - `version`: The `FORMAT_VERSION` the backup was written with.
+/// - `overlays`: Recipe overlays keyed by recipe ID.
+/// - `cook_log`: Every cook log entry, keyed by its record ID.
+/// - `pantry`: Every pantry item.
+#[derive(Debug, Default, Serialize, Deserialize, Clone, PartialEq)]
+pub struct UserData {
+    pub version: u32,
+    pub overlays: Vec<(String, RecipeOverlay)>,
+    pub cook_log: Vec<(String, CookLogEntry)>,
+    pub pantry: Vec<PantryItem>,
+}
+
+/// Reads all user-generated data out of the database.
+pub async fn export(db: &Surreal<Client>) -> Result<UserData, surrealdb::Error> {
+    Ok(UserData {
+        version: FORMAT_VERSION,
+        overlays: overlay::list(db).await?,
+        cook_log: cook_log::list(db).await?,
+        pantry: pantry::list(db).await?,
+    })
+}
+
+/// Writes all user-generated data to a JSON file at `path`.
+pub async fn export_to(db: &Surreal<Client>, path: &Path) -> Result<(), Box<dyn Error>> {
+    let data = export(db).await?;
+    fs::write(path, serde_json::to_string_pretty(&data)?)?;
+    Ok(())
+}
+
+/// Restores user-generated data into the database.
+///
+/// Every record replaces the existing record with the same ID, so importing the same
+/// backup twice changes nothing. Cook log entries are restored without bumping overlay
+/// counters a second time.
+///
+/// # Errors
+///
+/// Returns an error if the backup was written by a newer format version, or a write fails.
+pub async fn import(db: &Surreal<Client>, data: UserData) -> Result<(), Box<dyn Error>> {
+    if data.version > FORMAT_VERSION {
+        Err(format!(
+            "User data format version {} is newer than supported version {}",
+            data.version, FORMAT_VERSION
+        ))?
+    }
+
+    for (recipe_id, o) in data.overlays {
+        overlay::put(db, &recipe_id, o).await?;
+    }
+    for (id, entry) in data.cook_log {
+        cook_log::restore(db, &id, entry).await?;
+    }
+    for item in data.pantry {
+        pantry::upsert(db, item).await?;
+    }
+
+    Ok(())
+}
+
+/// Restores user-generated data from a JSON file written by `export_to`.
+pub async fn import_from(db: &Surreal<Client>, path: &Path) -> Result<(), Box<dyn Error>> {
+    let data: UserData = serde_json::from_str(&fs::read_to_string(path)?)?;
+    import(db, data).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::db::conn;
+
+    #[tokio::test]
+    async fn import_twice() {
+        let db = conn().await.expect("Failed to connect to db:");
+
+        cook_log::log(
+            &db,
+            CookLogEntry {
+                recipe_id: "easy-meat-lasagna".into(),
+                cooked_on: "2024-06-20".into(),
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to log a cooking");
+        pantry::upsert(
+            &db,
+            PantryItem {
+                name: "Crushed tomatoes".into(),
+                quantity: 2.0,
+                ..Default::default()
+            },
+        )
+        .await
+        .expect("Failed to add a pantry item");
+
+        let data = export(&db).await.expect("Failed to export user data");
+        import(&db, data.clone()).await.expect("Failed to import");
+        import(&db, data.clone())
+            .await
+            .expect("Failed to import again");
+        let after = export(&db).await.expect("Failed to export user data");
+
+        assert_eq!(after.overlays.len(), data.overlays.len());
+        assert_eq!(after.cook_log.len(), data.cook_log.len());
+        assert_eq!(after.pantry.len(), data.pantry.len());
+    }
+}
@@ -2,6 +2,9 @@ use regex::Regex;
 
 pub trait U32Ext {
     fn from_time_str(str: &str) -> Result<u32, Box<dyn std::error::Error>>;
+    fn from_iso_duration(str: &str) -> Result<u32, Box<dyn std::error::Error>>;
+    fn to_time_str(&self) -> String;
+    fn to_iso_duration(&self) -> String;
 }
 
 impl U32Ext for u32 {
@@ -9,13 +12,243 @@ impl U32Ext for u32 {
         let re = Regex::new(r"(?:\s*(\d+)\s*(?:hour|hr)s?)?(?:\s*(\d+)\s*(?:minute|min)s?)?")?;
         let caps = re.captures(str).ok_or("Failed to parse duration")?;
 
-        let hours = caps.get(1).map_or(0, |m| {
-            m.as_str().parse::<u32>().expect("Cannot parse hours")
-        });
-        let minutes = caps.get(2).map_or(0, |m| {
-            m.as_str().parse::<u32>().expect("Cannot parse minutes")
-        });
+        let hours = caps.get(1).map_or(Ok(0), |m| m.as_str().parse::<u32>())?;
+        let minutes = caps.get(2).map_or(Ok(0), |m| m.as_str().parse::<u32>())?;
 
-        Ok(hours * 60 + minutes)
+        hours
+            .checked_mul(60)
+            .and_then(|h| h.checked_add(minutes))
+            .ok_or_else(|| format!("Duration out of range: {}", str).into())
+    }
+
+    /// Parses an ISO 8601 duration (`PT1H30M`, `P1DT2H`) into minutes, as used by
+    /// schema.org. Seconds are rounded up to the next minute.
+    fn from_iso_duration(str: &str) -> Result<u32, Box<dyn std::error::Error>> {
+        let re = Regex::new(r"^P(?:(\d+)D)?(?:T(?:(\d+)H)?(?:(\d+)M)?(?:(\d+(?:\.\d+)?)S)?)?$")?;
+        let caps = re
+            .captures(str.trim())
+            .ok_or_else(|| format!("Not an ISO 8601 duration: {}", str))?;
+
+        let part = |i: usize| caps.get(i).map_or(Ok(0), |m| m.as_str().parse::<u32>());
+        let (days, hours, minutes) = (part(1)?, part(2)?, part(3)?);
+        let seconds = caps.get(4).map_or(Ok(0.0), |m| m.as_str().parse::<f64>())?;
+
+        days.checked_mul(24 * 60)
+            .and_then(|t| t.checked_add(hours.checked_mul(60)?))
+            .and_then(|t| t.checked_add(minutes))
+            .and_then(|t| t.checked_add((seconds / 60.0).ceil() as u32))
+            .ok_or_else(|| format!("Duration out of range: {}", str).into())
+    }
+
+    /// Formats a duration in minutes for people, e.g. `90` as `1 hr 30 min`.
+    /// The inverse of `from_time_str`.
+    fn to_time_str(&self) -> String {
+        let (hours, minutes) = (self / 60, self % 60);
+
+        match (hours, minutes) {
+            (0, m) => format!("{} min", m),
+            (1, 0) => "1 hr".to_string(),
+            (h, 0) => format!("{} hrs", h),
+            (1, m) => format!("1 hr {} min", m),
+            (h, m) => format!("{} hrs {} min", h, m),
+        }
+    }
+
+    /// Formats a duration in minutes as ISO 8601, e.g. `90` as `PT1H30M`.
+    fn to_iso_duration(&self) -> String {
+        let (hours, minutes) = (self / 60, self % 60);
+
+        match (hours, minutes) {
+            (0, m) => format!("PT{}M", m),
+            (h, 0) => format!("PT{}H", h),
+            (h, m) => format!("PT{}H{}M", h, m),
+        }
+    }
+}
+
+/// How renderers print durations.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum TimeFormat {
+    /// `1 hr 30 min`
+    #[default]
+    Human,
+    /// `PT1H30M`
+    Iso8601,
+    /// `1 hr 30 min (PT1H30M)`
+    Both,
+}
+
+impl TimeFormat {
+    pub fn format(&self, minutes: u32) -> String {
+        match self {
+            TimeFormat::Human => minutes.to_time_str(),
+            TimeFormat::Iso8601 => minutes.to_iso_duration(),
+            TimeFormat::Both => {
+                format!("{} ({})", minutes.to_time_str(), minutes.to_iso_duration())
+            }
+        }
+    }
+}
+
+/// Unicode vulgar fractions and their ASCII forms.
+const FRACTIONS: &[(char, &str)] = &[
+    ('¼', "1/4"),
+    ('½', "1/2"),
+    ('¾', "3/4"),
+    ('⅓', "1/3"),
+    ('⅔', "2/3"),
+    ('⅛', "1/8"),
+    ('⅜', "3/8"),
+    ('⅝', "5/8"),
+    ('⅞', "7/8"),
+];
+
+/// Which typography differences `Normalize::apply` irons out. Both are on by default.
+///
+/// # Fields
+///
+/// - `fractions`: Rewrite fraction glyphs as ASCII (`1½` → `1 1/2`, `1⁄2` → `1/2`).
+/// - `punctuation`: Rewrite smart quotes, dashes, ellipses and non-breaking spaces as ASCII.
+#[derive(Debug, Clone, Copy)]
+pub struct Normalize {
+    pub fractions: bool,
+    pub punctuation: bool,
+}
+
+impl Default for Normalize {
+    fn default() -> Self {
+        Normalize {
+            fractions: true,
+            punctuation: true,
+        }
+    }
+}
+
+impl Normalize {
+    pub fn apply(&self, text: &str) -> String {
+        let mut out = String::with_capacity(text.len());
+
+        for c in text.chars() {
+            if self.fractions {
+                if let Some((_, ascii)) = FRACTIONS.iter().find(|(glyph, _)| *glyph == c) {
+                    if out.ends_with(|p: char| p.is_ascii_digit()) {
+                        out.push(' ');
+                    }
+                    out.push_str(ascii);
+                    continue;
+                }
+                if c == '⁄' {
+                    out.push('/');
+                    continue;
+                }
+            }
+
+            if self.punctuation {
+                match c {
+                    '‘' | '’' | '′' => out.push('\''),
+                    '“' | '”' | '″' => out.push('"'),
+                    '–' | '—' | '‐' => out.push('-'),
+                    '…' => out.push_str("..."),
+                    '\u{a0}' | '\u{2009}' | '\u{202f}' => out.push(' '),
+                    _ => out.push(c),
+                }
+            } else {
+                out.push(c);
+            }
+        }
+
+        out
+    }
+}
+
+/// Normalizes fraction glyphs and smart punctuation with the default `Normalize` options,
+/// so quantity parsing isn't defeated by typography.
+pub fn normalize_text(text: &str) -> String {
+    Normalize::default().apply(text)
+}
+
+/// Checks that `date` is a real calendar date written as `YYYY-MM-DD`, the form user
+/// data stores dates in so they sort as strings.
+///
+/// # Returns
+/// - The date with surrounding whitespace trimmed.
+///
+/// # Errors
+///
+/// Returns an error if `date` is in another format or names a day that doesn't exist.
+pub fn parse_iso_date(date: &str) -> Result<String, Box<dyn std::error::Error>> {
+    let date = date.trim();
+    let re = Regex::new(r"^(\d{4})-(\d{2})-(\d{2})$")?;
+    let caps = re
+        .captures(date)
+        .ok_or_else(|| format!("Not a YYYY-MM-DD date: {}", date))?;
+
+    let year = caps[1].parse::<u32>()?;
+    let month = caps[2].parse::<u32>()?;
+    let day = caps[3].parse::<u32>()?;
+
+    let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+    let days_in_month = match month {
+        2 if leap => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        1..=12 => 31,
+        _ => 0,
+    };
+    if !(1..=days_in_month).contains(&day) {
+        Err(format!("No such date: {}", date))?
+    }
+
+    Ok(date.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_formats() {
+        assert_eq!(90.to_time_str(), "1 hr 30 min");
+        assert_eq!(120.to_time_str(), "2 hrs");
+        assert_eq!(45.to_time_str(), "45 min");
+        assert_eq!(u32::from_time_str(&135.to_time_str()).unwrap(), 135);
+
+        assert_eq!(90.to_iso_duration(), "PT1H30M");
+        assert_eq!(60.to_iso_duration(), "PT1H");
+        assert_eq!(0.to_iso_duration(), "PT0M");
+
+        assert_eq!(TimeFormat::Both.format(75), "1 hr 15 min (PT1H15M)");
+
+        assert_eq!(u32::from_iso_duration("PT1H30M").unwrap(), 90);
+        assert_eq!(u32::from_iso_duration("P1DT2H").unwrap(), 1560);
+        assert_eq!(u32::from_iso_duration("PT90S").unwrap(), 2);
+        assert!(u32::from_iso_duration("1 hour").is_err());
+    }
+
+    #[test]
+    fn test_normalize_text() {
+        assert_eq!(normalize_text("1½ cups"), "1 1/2 cups");
+        assert_eq!(normalize_text("¾ cup Rao’s sauce"), "3/4 cup Rao's sauce");
+        assert_eq!(
+            normalize_text("1⁄4\u{a0}tsp – “heaping”…"),
+            "1/4 tsp - \"heaping\"..."
+        );
+
+        let fractions_only = Normalize {
+            fractions: true,
+            punctuation: false,
+        };
+        assert_eq!(fractions_only.apply("½ – ‘x’"), "1/2 – ‘x’");
+    }
+
+    #[test]
+    fn test_parse_iso_date() {
+        assert_eq!(parse_iso_date(" 2024-02-29 ").unwrap(), "2024-02-29");
+        assert!(parse_iso_date("2023-02-29").is_err());
+        assert!(parse_iso_date("2024-13-01").is_err());
+        assert!(parse_iso_date("2024-04-31").is_err());
+        assert!(parse_iso_date("2024-3-1").is_err());
+        assert!(parse_iso_date("03/01/2024").is_err());
+        assert!(parse_iso_date("").is_err());
     }
 }
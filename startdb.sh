@@ -1 +1 @@
-surreal start --user root --pass root --bind 127.0.0.1:8080
+surreal start --user "${SURREAL_USER:-root}" --pass "${SURREAL_PASS:-root}" --bind "${SURREAL_ADDR:-127.0.0.1:8080}"